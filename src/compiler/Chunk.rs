@@ -0,0 +1,71 @@
+use std::{collections::HashMap, rc::Rc};
+
+use super::OpCode::OpCode;
+
+/// Values known at compile time and stored in a chunk's constant table.
+#[derive(Clone)]
+pub enum Constant {
+    Number(f64),
+    String(Rc<str>),
+    Function(Rc<Function>),
+}
+
+/// A compiled function body. The top-level script is a `Function` too.
+pub struct Function {
+    pub name: String,
+    pub arity: usize,
+    pub upvalue_count: usize,
+    pub chunk: Chunk,
+    /// A method declared without a parameter list; property reads call it.
+    pub is_getter: bool,
+    /// The id of the source file it was compiled from; see
+    /// [`Span::file`](crate::implementation::Token::Span::file).
+    pub file: u32,
+}
+
+#[derive(Default)]
+pub struct Chunk {
+    pub code: Vec<u8>,
+    pub constants: Vec<Constant>,
+    /// Run-length encoded line table: `(line, number of bytes on that line)`.
+    pub lines: Vec<(u32, usize)>,
+    strings: HashMap<Rc<str>, usize>,
+}
+
+impl Chunk {
+    pub fn write(&mut self, byte: u8, line: u32) {
+        self.code.push(byte);
+        match self.lines.last_mut() {
+            Some((last_line, count)) if *last_line == line => *count += 1,
+            _ => self.lines.push((line, 1)),
+        }
+    }
+
+    pub fn write_op(&mut self, op: OpCode, line: u32) {
+        self.write(op as u8, line);
+    }
+
+    /// Adds a constant and returns its index. Strings are interned per chunk
+    /// so repeated identifiers share one slot.
+    pub fn add_constant(&mut self, constant: Constant) -> usize {
+        if let Constant::String(s) = &constant {
+            if let Some(&index) = self.strings.get(s) {
+                return index;
+            }
+            self.strings.insert(Rc::clone(s), self.constants.len());
+        }
+        self.constants.push(constant);
+        self.constants.len() - 1
+    }
+
+    pub fn line_at(&self, offset: usize) -> u32 {
+        let mut seen = 0;
+        for (line, count) in &self.lines {
+            seen += count;
+            if offset < seen {
+                return *line;
+            }
+        }
+        self.lines.last().map(|(line, _)| *line).unwrap_or(0)
+    }
+}
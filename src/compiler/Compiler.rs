@@ -0,0 +1,998 @@
+use std::rc::Rc;
+
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError, TokenType::TokenType},
+    implementation::{
+        AssignmentExpression::AssignmentExpression,
+        BinaryExpression::BinaryExpression,
+        BlockStatement::BlockStatement,
+        BreakStatement::BreakStatement,
+        CallExpression::CallExpression,
+        ClassStatement::ClassStatement,
+        ContinueStatement::ContinueStatement,
+        ExpressionStatement::ExpressionStatement,
+        FunctionExpression::FunctionExpression,
+        FunctionStatement::FunctionStatement,
+        GetExpression::GetExpression,
+        Grouping::Grouping,
+        IfStatement::IfStatement,
+        ImportStatement::ImportStatement,
+        IndexExpression::IndexExpression,
+        IndexSetExpression::IndexSetExpression,
+        ListExpression::ListExpression,
+        Literal::Literal,
+        LogicalExpression::LogicalExpression,
+        MapExpression::MapExpression,
+        PrintStatement::PrintStatement,
+        Resolver::FunctionType,
+        ReturnStatement::ReturnStatement,
+        SetExpression::SetExpression,
+        SuperExpression::SuperExpression,
+        ThisExpression::ThisExpression,
+        ThrowStatement::ThrowStatement,
+        Token::{Span, Token},
+        TryStatement::TryStatement,
+        UnaryExpression::UnaryExpression,
+        VariableExpression::VariableExpression,
+        VariableStatement::VariableStatement,
+        WhileStatement::WhileStatement,
+    },
+    traits::Statement::Statement,
+};
+
+use super::{
+    Chunk::{Chunk, Constant, Function},
+    OpCode::OpCode,
+};
+
+/// The name of the function a script or module's top level compiles to.
+pub const SCRIPT: &str = "<script>";
+
+struct Local {
+    name: String,
+    /// `None` while the local's initializer is still being compiled.
+    depth: Option<usize>,
+    is_captured: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Upvalue {
+    index: u8,
+    is_local: bool,
+}
+
+/// A loop whose body is being compiled, with the `break` and `continue`
+/// jumps that still need patching once their targets are known.
+struct Loop {
+    /// Scope depth just outside the body; deeper locals are discarded before
+    /// jumping out.
+    scope_depth: usize,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+/// A `try` statement being compiled, for the `return`, `break` and
+/// `continue` statements that jump out of it.
+#[derive(Clone)]
+struct Try {
+    /// Scope depth just outside the statement.
+    scope_depth: usize,
+    /// How many loops enclose the statement.
+    loop_depth: usize,
+    /// Exception handlers still installed at this point of the statement.
+    handlers: usize,
+    /// Set inside the `finally` block that runs for an error, which is
+    /// set aside until the block finishes.
+    pending: bool,
+    /// The `finally` block a jump out of here still has to run.
+    finally_block: Option<BlockStatement>,
+}
+
+/// Per-function compilation state; one is pushed for every function body.
+struct FunctionState {
+    function: Function,
+    kind: FunctionType,
+    locals: Vec<Local>,
+    upvalues: Vec<Upvalue>,
+    scope_depth: usize,
+    loops: Vec<Loop>,
+    tries: Vec<Try>,
+}
+
+impl FunctionState {
+    fn new(name: String, kind: FunctionType, file: u32) -> Self {
+        // Slot zero holds the callee, or the receiver for methods.
+        let slot_zero = match kind {
+            FunctionType::METHOD | FunctionType::INITIALIZER => "this",
+            _ => "",
+        };
+        FunctionState {
+            function: Function {
+                name,
+                arity: 0,
+                upvalue_count: 0,
+                chunk: Chunk::default(),
+                is_getter: false,
+                file,
+            },
+            kind,
+            locals: vec![Local {
+                name: slot_zero.to_string(),
+                depth: Some(0),
+                is_captured: false,
+            }],
+            upvalues: Vec::new(),
+            scope_depth: 0,
+            loops: Vec::new(),
+            tries: Vec::new(),
+        }
+    }
+}
+
+/// Lowers a resolved AST into bytecode for the `Vm`.
+pub struct Compiler {
+    states: Vec<FunctionState>,
+    class_has_superclass: Vec<bool>,
+    line: u32,
+    errors: Vec<LoxError>,
+    file: u32,
+}
+
+impl Compiler {
+    pub fn new() -> Self {
+        Compiler {
+            states: Vec::new(),
+            class_has_superclass: Vec::new(),
+            line: 1,
+            errors: Vec::new(),
+            file: 0,
+        }
+    }
+
+    /// Marks the code as coming from source file `file`, for an imported
+    /// module. The main script is file 0.
+    pub fn in_file(mut self, file: u32) -> Self {
+        self.file = file;
+        self
+    }
+
+    pub fn compile(
+        &mut self,
+        statements: &mut Vec<Box<dyn Statement>>,
+    ) -> Result<Rc<Function>, Vec<LoxError>> {
+        self.states.push(FunctionState::new(
+            String::from(SCRIPT),
+            FunctionType::NONE,
+            self.file,
+        ));
+        for statement in statements {
+            statement.compile(self);
+        }
+        let function = self.end_function();
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+        Ok(Rc::new(function))
+    }
+
+    fn error(&mut self, message: &str, token: &Token) {
+        self.errors
+            .push(LoxError::parse(token, message.to_string()));
+    }
+
+    fn state(&mut self) -> &mut FunctionState {
+        self.states
+            .last_mut()
+            .expect("compiler has no active function")
+    }
+
+    fn chunk(&mut self) -> &mut Chunk {
+        &mut self.state().function.chunk
+    }
+
+    fn emit_byte(&mut self, byte: u8) {
+        let line = self.line;
+        self.chunk().write(byte, line);
+    }
+
+    fn emit_op(&mut self, op: OpCode) {
+        let line = self.line;
+        self.chunk().write_op(op, line);
+    }
+
+    fn emit_short(&mut self, value: u16) {
+        self.emit_byte((value >> 8) as u8);
+        self.emit_byte((value & 0xff) as u8);
+    }
+
+    fn make_constant(&mut self, constant: Constant, token: &Token) -> u16 {
+        let index = self.chunk().add_constant(constant);
+        if index > u16::MAX as usize {
+            self.error("Too many constants in one chunk.", token);
+            return 0;
+        }
+        index as u16
+    }
+
+    fn identifier_constant(&mut self, name: &Token) -> u16 {
+        self.make_constant(Constant::String(Rc::from(name.token_value.as_str())), name)
+    }
+
+    fn emit_constant(&mut self, constant: Constant, token: &Token) {
+        let index = self.make_constant(constant, token);
+        self.emit_op(OpCode::CONSTANT);
+        self.emit_short(index);
+    }
+
+    fn emit_jump(&mut self, op: OpCode) -> usize {
+        self.emit_op(op);
+        self.emit_short(u16::MAX);
+        self.chunk().code.len() - 2
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        let jump = self.chunk().code.len() - offset - 2;
+        if jump > u16::MAX as usize {
+            let token = self.synthetic_token("");
+            self.error("Too much code to jump over.", &token);
+        }
+        let code = &mut self.chunk().code;
+        code[offset] = ((jump >> 8) & 0xff) as u8;
+        code[offset + 1] = (jump & 0xff) as u8;
+    }
+
+    fn emit_loop(&mut self, loop_start: usize) {
+        self.emit_op(OpCode::LOOP);
+        let offset = self.chunk().code.len() - loop_start + 2;
+        if offset > u16::MAX as usize {
+            let token = self.synthetic_token("");
+            self.error("Loop body too large.", &token);
+        }
+        self.emit_short(offset as u16);
+    }
+
+    /// Pushes what a bare `return` returns: `this` in initializers, else nil.
+    fn emit_default_result(&mut self) {
+        if self.state().kind == FunctionType::INITIALIZER {
+            self.emit_op(OpCode::GET_LOCAL);
+            self.emit_byte(0);
+        } else {
+            self.emit_op(OpCode::NIL);
+        }
+    }
+
+    fn emit_return(&mut self) {
+        self.emit_default_result();
+        self.emit_op(OpCode::RETURN);
+    }
+
+    fn end_function(&mut self) -> Function {
+        self.emit_return();
+        let state = self.states.pop().expect("compiler has no active function");
+        let mut function = state.function;
+        function.upvalue_count = state.upvalues.len();
+        function
+    }
+
+    fn synthetic_token(&self, text: &str) -> Token {
+        Token {
+            token_type: TokenType::IDENTIFIER,
+            token_value: text.to_string(),
+            line: self.line,
+            span: Span::default(),
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.state().scope_depth += 1;
+    }
+
+    fn end_scope(&mut self) {
+        self.state().scope_depth -= 1;
+        loop {
+            let state = self.state();
+            let captured = match state.locals.last() {
+                Some(local) if local.depth.is_some_and(|d| d > state.scope_depth) => {
+                    local.is_captured
+                }
+                _ => break,
+            };
+            state.locals.pop();
+            if captured {
+                self.emit_op(OpCode::CLOSE_UPVALUE);
+            } else {
+                self.emit_op(OpCode::POP);
+            }
+        }
+    }
+
+    /// Pops the locals declared inside the innermost loop without forgetting
+    /// them, for a `break` or `continue` that leaves their scopes early.
+    fn discard_loop_locals(&mut self) {
+        let state = self.state();
+        let Some(depth) = state.loops.last().map(|current| current.scope_depth) else {
+            return;
+        };
+        let captured: Vec<bool> = state
+            .locals
+            .iter()
+            .rev()
+            .take_while(|local| local.depth.is_some_and(|d| d > depth))
+            .map(|local| local.is_captured)
+            .collect();
+        for is_captured in captured {
+            if is_captured {
+                self.emit_op(OpCode::CLOSE_UPVALUE);
+            } else {
+                self.emit_op(OpCode::POP);
+            }
+        }
+    }
+
+    /// Leaves the `try` statements from `first` inwards, innermost first:
+    /// their handlers are removed, a set-aside error is forgotten and their
+    /// `finally` blocks run.
+    fn exit_tries(&mut self, first: usize) {
+        for index in (first..self.state().tries.len()).rev() {
+            let exited = self.state().tries[index].clone();
+            for _ in 0..exited.handlers {
+                self.emit_op(OpCode::END_TRY);
+            }
+            if exited.pending {
+                self.emit_op(OpCode::DROP_PENDING);
+            }
+            if let Some(mut finally_block) = exited.finally_block.clone() {
+                self.inline_finally(index, &exited, &mut finally_block);
+            }
+        }
+    }
+
+    /// Compiles a copy of `exited`'s `finally` block where a jump leaves the
+    /// statement. Locals declared inside it are still on the stack but
+    /// hidden from the block, and so are the statement itself and any loops
+    /// inside it, which the block's own jumps must not see.
+    fn inline_finally(&mut self, index: usize, exited: &Try, finally_block: &mut BlockStatement) {
+        let state = self.state();
+        let inner_tries = state.tries.split_off(index);
+        let inner_loops = state.loops.split_off(exited.loop_depth);
+        let hidden: Vec<(usize, String)> = state
+            .locals
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, local)| local.depth.is_some_and(|d| d > exited.scope_depth))
+            .map(|(slot, local)| (slot, std::mem::take(&mut local.name)))
+            .collect();
+        self.visit_block_statement(finally_block);
+        let state = self.state();
+        for (slot, name) in hidden {
+            state.locals[slot].name = name;
+        }
+        state.tries.extend(inner_tries);
+        state.loops.extend(inner_loops);
+    }
+
+    /// The `try` statements a `break` or `continue` leaves: the ones inside
+    /// the innermost loop.
+    fn tries_in_loop(&mut self) -> usize {
+        let state = self.state();
+        let loop_depth = state.loops.len();
+        state
+            .tries
+            .iter()
+            .position(|current| current.loop_depth >= loop_depth)
+            .unwrap_or(state.tries.len())
+    }
+
+    fn add_local(&mut self, name: &Token) {
+        if self.state().locals.len() > u8::MAX as usize {
+            self.error("Too many local variables in function.", name);
+            return;
+        }
+        self.state().locals.push(Local {
+            name: name.token_value.clone(),
+            depth: None,
+            is_captured: false,
+        });
+    }
+
+    fn mark_initialized(&mut self) {
+        let state = self.state();
+        let depth = state.scope_depth;
+        if depth == 0 {
+            return;
+        }
+        if let Some(local) = state.locals.last_mut() {
+            local.depth = Some(depth);
+        }
+    }
+
+    /// Declares `name` as a local when inside a scope. Globals are late bound
+    /// and need no declaration.
+    fn declare_variable(&mut self, name: &Token) {
+        if self.state().scope_depth == 0 {
+            return;
+        }
+        self.add_local(name);
+    }
+
+    fn define_variable(&mut self, name: &Token) {
+        if self.state().scope_depth > 0 {
+            self.mark_initialized();
+            return;
+        }
+        let global = self.identifier_constant(name);
+        self.emit_op(OpCode::DEFINE_GLOBAL);
+        self.emit_short(global);
+    }
+
+    fn resolve_local(&self, state_index: usize, name: &str) -> Option<u8> {
+        self.states[state_index]
+            .locals
+            .iter()
+            .rposition(|local| local.name == name)
+            .map(|slot| slot as u8)
+    }
+
+    fn add_upvalue(&mut self, state_index: usize, index: u8, is_local: bool, name: &Token) -> u8 {
+        let upvalue = Upvalue { index, is_local };
+        let upvalues = &self.states[state_index].upvalues;
+        if let Some(existing) = upvalues.iter().position(|u| *u == upvalue) {
+            return existing as u8;
+        }
+        if upvalues.len() > u8::MAX as usize {
+            self.error("Too many closure variables in function.", name);
+            return 0;
+        }
+        self.states[state_index].upvalues.push(upvalue);
+        (self.states[state_index].upvalues.len() - 1) as u8
+    }
+
+    fn resolve_upvalue(&mut self, state_index: usize, name: &Token) -> Option<u8> {
+        if state_index == 0 {
+            return None;
+        }
+        if let Some(local) = self.resolve_local(state_index - 1, &name.token_value) {
+            self.states[state_index - 1].locals[local as usize].is_captured = true;
+            return Some(self.add_upvalue(state_index, local, true, name));
+        }
+        if let Some(upvalue) = self.resolve_upvalue(state_index - 1, name) {
+            return Some(self.add_upvalue(state_index, upvalue, false, name));
+        }
+        None
+    }
+
+    /// Emits the get (or, when `assign` is set, set) instruction for a variable
+    /// reference, picking local, upvalue or global access.
+    fn named_variable(&mut self, name: &Token, assign: bool) {
+        self.line = name.line;
+        let current = self.states.len() - 1;
+        let (op, operand) = if let Some(slot) = self.resolve_local(current, &name.token_value) {
+            let op = if assign {
+                OpCode::SET_LOCAL
+            } else {
+                OpCode::GET_LOCAL
+            };
+            (op, slot as u16)
+        } else if let Some(index) = self.resolve_upvalue(current, name) {
+            let op = if assign {
+                OpCode::SET_UPVALUE
+            } else {
+                OpCode::GET_UPVALUE
+            };
+            (op, index as u16)
+        } else {
+            let op = if assign {
+                OpCode::SET_GLOBAL
+            } else {
+                OpCode::GET_GLOBAL
+            };
+            (op, self.identifier_constant(name))
+        };
+        self.emit_op(op);
+        match op {
+            OpCode::GET_GLOBAL | OpCode::SET_GLOBAL => self.emit_short(operand),
+            _ => self.emit_byte(operand as u8),
+        }
+    }
+
+    fn function(&mut self, statement: &mut FunctionStatement, kind: FunctionType) {
+        self.line = statement.name.line;
+        self.states.push(FunctionState::new(
+            statement.name.token_value.clone(),
+            kind,
+            self.file,
+        ));
+        self.begin_scope();
+        self.state().function.arity = statement.parameters.len();
+        self.state().function.is_getter = statement.is_getter;
+        for parameter in &statement.parameters {
+            self.declare_variable(parameter);
+            self.define_variable(parameter);
+        }
+        for body_statement in &mut statement.body {
+            body_statement.compile(self);
+        }
+        let upvalues = self.state().upvalues.clone();
+        let function = self.end_function();
+
+        self.line = statement.name.line;
+        let index = self.make_constant(Constant::Function(Rc::new(function)), &statement.name);
+        self.emit_op(OpCode::CLOSURE);
+        self.emit_short(index);
+        for upvalue in upvalues {
+            self.emit_byte(upvalue.is_local as u8);
+            self.emit_byte(upvalue.index);
+        }
+    }
+
+    pub fn visit_expression_statement(&mut self, statement: &mut ExpressionStatement) {
+        statement.expression.compile(self);
+        self.emit_op(OpCode::POP);
+    }
+
+    pub fn visit_print_statement(&mut self, statement: &mut PrintStatement) {
+        statement.expression.compile(self);
+        self.line = statement.keyword.line;
+        self.emit_op(OpCode::PRINT);
+    }
+
+    pub fn visit_variable_statement(&mut self, statement: &mut VariableStatement) {
+        self.declare_variable(&statement.name);
+        statement.initializer.compile(self);
+        self.line = statement.name.line;
+        self.define_variable(&statement.name);
+    }
+
+    pub fn visit_block_statement(&mut self, statement: &mut BlockStatement) {
+        self.begin_scope();
+        for stmt in &mut statement.statements {
+            stmt.compile(self);
+        }
+        self.end_scope();
+    }
+
+    pub fn visit_if_statement(&mut self, statement: &mut IfStatement) {
+        statement.condition.compile(self);
+        let then_jump = self.emit_jump(OpCode::JUMP_IF_FALSE);
+        self.emit_op(OpCode::POP);
+        statement.then_statement.compile(self);
+        let else_jump = self.emit_jump(OpCode::JUMP);
+        self.patch_jump(then_jump);
+        self.emit_op(OpCode::POP);
+        if let Some(else_statement) = &mut statement.else_statement {
+            else_statement.compile(self);
+        }
+        self.patch_jump(else_jump);
+    }
+
+    pub fn visit_while_statement(&mut self, statement: &mut WhileStatement) {
+        let loop_start = self.chunk().code.len();
+        statement.condition.compile(self);
+        let exit_jump = self.emit_jump(OpCode::JUMP_IF_FALSE);
+        self.emit_op(OpCode::POP);
+        let scope_depth = self.state().scope_depth;
+        self.state().loops.push(Loop {
+            scope_depth,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+        statement.body.compile(self);
+        let current = self.state().loops.pop().expect("loop was pushed above");
+        for jump in current.continues {
+            self.patch_jump(jump);
+        }
+        if let Some(increment) = &mut statement.increment {
+            increment.compile(self);
+            self.emit_op(OpCode::POP);
+        }
+        self.emit_loop(loop_start);
+        self.patch_jump(exit_jump);
+        self.emit_op(OpCode::POP);
+        for jump in current.breaks {
+            self.patch_jump(jump);
+        }
+    }
+
+    pub fn visit_import_statement(&mut self, statement: &mut ImportStatement) {
+        self.line = statement.keyword.line;
+        let path = Constant::String(Rc::from(statement.path.token_value.as_str()));
+        let path = self.make_constant(path, &statement.path);
+        let name = self.identifier_constant(&statement.name);
+        self.declare_variable(&statement.name);
+        self.emit_op(OpCode::IMPORT);
+        self.emit_short(path);
+        self.emit_short(name);
+        self.define_variable(&statement.name);
+    }
+
+    pub fn visit_throw_statement(&mut self, statement: &mut ThrowStatement) {
+        statement.value.compile(self);
+        self.line = statement.keyword.line;
+        self.emit_op(OpCode::THROW);
+    }
+
+    /// `try { B } catch (e) { C } finally { F }` compiles to
+    ///
+    /// ```text
+    ///     TRY_FINALLY -> error
+    ///     TRY -> catch
+    ///     B
+    ///     END_TRY
+    ///     JUMP -> caught
+    /// catch:                  the caught value is `e`'s slot
+    ///     C
+    /// caught:
+    ///     END_TRY
+    ///     F
+    ///     JUMP -> end
+    /// error:                  the error is set aside
+    ///     F
+    ///     RETHROW
+    /// end:
+    /// ```
+    ///
+    /// leaving out the parts for a missing clause.
+    pub fn visit_try_statement(&mut self, statement: &mut TryStatement) {
+        self.line = statement.keyword.line;
+        let scope_depth = self.state().scope_depth;
+        let loop_depth = self.state().loops.len();
+        let finally_handler = statement
+            .finally_block
+            .as_ref()
+            .map(|_| self.emit_jump(OpCode::TRY_FINALLY));
+        let catch_handler = statement
+            .catch_clause
+            .as_ref()
+            .map(|_| self.emit_jump(OpCode::TRY));
+        self.state().tries.push(Try {
+            scope_depth,
+            loop_depth,
+            handlers: usize::from(finally_handler.is_some()) + usize::from(catch_handler.is_some()),
+            pending: false,
+            finally_block: statement.finally_block.clone(),
+        });
+        self.visit_block_statement(&mut statement.body);
+
+        if let (Some(handler), Some((name, body))) = (catch_handler, &mut statement.catch_clause) {
+            self.emit_op(OpCode::END_TRY);
+            let caught = self.emit_jump(OpCode::JUMP);
+            self.patch_jump(handler);
+            self.current_try().handlers -= 1;
+            self.begin_scope();
+            self.add_local(name);
+            self.mark_initialized();
+            for stmt in &mut body.statements {
+                stmt.compile(self);
+            }
+            self.end_scope();
+            self.patch_jump(caught);
+        }
+        self.state().tries.pop();
+
+        if let (Some(handler), Some(finally_block)) =
+            (finally_handler, &mut statement.finally_block)
+        {
+            self.emit_op(OpCode::END_TRY);
+            self.visit_block_statement(finally_block);
+            let end = self.emit_jump(OpCode::JUMP);
+            self.patch_jump(handler);
+            self.state().tries.push(Try {
+                scope_depth,
+                loop_depth,
+                handlers: 0,
+                pending: true,
+                finally_block: None,
+            });
+            self.visit_block_statement(finally_block);
+            self.state().tries.pop();
+            self.emit_op(OpCode::RETHROW);
+            self.patch_jump(end);
+        }
+    }
+
+    fn current_try(&mut self) -> &mut Try {
+        self.state()
+            .tries
+            .last_mut()
+            .expect("compiler is not inside a try statement")
+    }
+
+    pub fn visit_break_statement(&mut self, statement: &mut BreakStatement) {
+        let first = self.tries_in_loop();
+        self.exit_tries(first);
+        self.line = statement.keyword.line;
+        self.discard_loop_locals();
+        let jump = self.emit_jump(OpCode::JUMP);
+        if let Some(current) = self.state().loops.last_mut() {
+            current.breaks.push(jump);
+        }
+    }
+
+    pub fn visit_continue_statement(&mut self, statement: &mut ContinueStatement) {
+        let first = self.tries_in_loop();
+        self.exit_tries(first);
+        self.line = statement.keyword.line;
+        self.discard_loop_locals();
+        let jump = self.emit_jump(OpCode::JUMP);
+        if let Some(current) = self.state().loops.last_mut() {
+            current.continues.push(jump);
+        }
+    }
+
+    pub fn visit_function_statement(&mut self, statement: &mut FunctionStatement) {
+        let name = statement.name.clone();
+        self.declare_variable(&name);
+        // A function may refer to itself, so it is usable before its body is compiled.
+        self.mark_initialized();
+        self.function(statement, FunctionType::FUNCTION);
+        self.define_variable(&name);
+    }
+
+    pub fn visit_return_statement(&mut self, statement: &mut ReturnStatement) {
+        self.line = statement.keyword.line;
+        match &mut statement.value {
+            Some(value) => value.compile(self),
+            None => self.emit_default_result(),
+        }
+        if !self.state().tries.is_empty() {
+            // The result waits in a slot of its own while `finally` blocks run.
+            let depth = self.state().scope_depth;
+            self.state().locals.push(Local {
+                name: String::new(),
+                depth: Some(depth),
+                is_captured: false,
+            });
+            self.exit_tries(0);
+            self.state().locals.pop();
+            self.line = statement.keyword.line;
+        }
+        self.emit_op(OpCode::RETURN);
+    }
+
+    pub fn visit_class_statement(&mut self, statement: &mut ClassStatement) {
+        let name = statement.name.clone();
+        self.line = name.line;
+        let name_constant = self.identifier_constant(&name);
+        self.declare_variable(&name);
+        self.emit_op(OpCode::CLASS);
+        self.emit_short(name_constant);
+        self.define_variable(&name);
+
+        self.class_has_superclass.push(false);
+        if let Some(superclass) = &mut statement.super_class {
+            self.visit_variable_expression(superclass);
+            self.begin_scope();
+            let super_token = self.synthetic_token("super");
+            self.add_local(&super_token);
+            self.define_variable(&super_token);
+            self.named_variable(&name, false);
+            self.emit_op(OpCode::INHERIT);
+            if let Some(has_superclass) = self.class_has_superclass.last_mut() {
+                *has_superclass = true;
+            }
+        }
+
+        self.named_variable(&name, false);
+        for method in &mut statement.methods {
+            if let Some(method_fn) = method.as_any_mut().downcast_mut::<FunctionStatement>() {
+                let kind = if method_fn.name.token_value.eq("init") {
+                    FunctionType::INITIALIZER
+                } else {
+                    FunctionType::METHOD
+                };
+                let method_name = method_fn.name.clone();
+                let method_constant = self.identifier_constant(&method_name);
+                self.function(method_fn, kind);
+                self.emit_op(OpCode::METHOD);
+                self.emit_short(method_constant);
+            } else {
+                unreachable!("ClassStatement.methods must all be functions");
+            }
+        }
+        for method in &mut statement.static_methods {
+            if let Some(method_fn) = method.as_any_mut().downcast_mut::<FunctionStatement>() {
+                let method_name = method_fn.name.clone();
+                let method_constant = self.identifier_constant(&method_name);
+                self.function(method_fn, FunctionType::METHOD);
+                self.emit_op(OpCode::STATIC_METHOD);
+                self.emit_short(method_constant);
+            } else {
+                unreachable!("ClassStatement.static_methods must all be functions");
+            }
+        }
+        for setter in &mut statement.setters {
+            if let Some(setter_fn) = setter.as_any_mut().downcast_mut::<FunctionStatement>() {
+                let setter_name = setter_fn.name.clone();
+                let setter_constant = self.identifier_constant(&setter_name);
+                self.function(setter_fn, FunctionType::METHOD);
+                self.emit_op(OpCode::SETTER);
+                self.emit_short(setter_constant);
+            } else {
+                unreachable!("ClassStatement.setters must all be functions");
+            }
+        }
+        self.emit_op(OpCode::POP);
+
+        if self.class_has_superclass.pop() == Some(true) {
+            self.end_scope();
+        }
+    }
+
+    pub fn visit_literal_expression(&mut self, expression: &mut Literal) {
+        let token = self.synthetic_token("");
+        match &expression.value {
+            LiteralValue::Nil => self.emit_op(OpCode::NIL),
+            LiteralValue::Boolean(true) => self.emit_op(OpCode::TRUE),
+            LiteralValue::Boolean(false) => self.emit_op(OpCode::FALSE),
+            LiteralValue::Number(n) => self.emit_constant(Constant::Number(*n), &token),
+            LiteralValue::String(s) => {
+                self.emit_constant(Constant::String(Rc::from(s.as_str())), &token)
+            }
+            _ => unreachable!("literals are only nil, booleans, numbers and strings"),
+        }
+    }
+
+    pub fn visit_grouping_expression(&mut self, expression: &mut Grouping) {
+        expression.expression.compile(self);
+    }
+
+    pub fn visit_unary_expression(&mut self, expression: &mut UnaryExpression) {
+        expression.expression.compile(self);
+        self.line = expression.operator.line;
+        match expression.operator.token_type {
+            TokenType::MINUS => self.emit_op(OpCode::NEGATE),
+            TokenType::BANG => self.emit_op(OpCode::NOT),
+            op => unreachable!("Unexpected unary operator: {:?}", op),
+        }
+    }
+
+    pub fn visit_binary_expression(&mut self, expression: &mut BinaryExpression) {
+        expression.left.compile(self);
+        expression.right.compile(self);
+        self.line = expression.operator.line;
+        match expression.operator.token_type {
+            TokenType::PLUS => self.emit_op(OpCode::ADD),
+            TokenType::MINUS => self.emit_op(OpCode::SUBTRACT),
+            TokenType::STAR => self.emit_op(OpCode::MULTIPLY),
+            TokenType::SLASH => self.emit_op(OpCode::DIVIDE),
+            TokenType::GREATER => self.emit_op(OpCode::GREATER),
+            TokenType::GREATER_EQUAL => self.emit_op(OpCode::GREATER_EQUAL),
+            TokenType::LESS => self.emit_op(OpCode::LESS),
+            TokenType::LESS_EQUAL => self.emit_op(OpCode::LESS_EQUAL),
+            TokenType::EQUAL_EQUAL => self.emit_op(OpCode::EQUAL),
+            TokenType::BANG_EQUAL => {
+                self.emit_op(OpCode::EQUAL);
+                self.emit_op(OpCode::NOT);
+            }
+            op => unreachable!("Unexpected binary operator: {:?}", op),
+        }
+    }
+
+    pub fn visit_logical_expression(&mut self, expression: &mut LogicalExpression) {
+        expression.left.compile(self);
+        self.line = expression.operator.line;
+        if expression.operator.token_type == TokenType::OR {
+            let else_jump = self.emit_jump(OpCode::JUMP_IF_FALSE);
+            let end_jump = self.emit_jump(OpCode::JUMP);
+            self.patch_jump(else_jump);
+            self.emit_op(OpCode::POP);
+            expression.right.compile(self);
+            self.patch_jump(end_jump);
+        } else {
+            let end_jump = self.emit_jump(OpCode::JUMP_IF_FALSE);
+            self.emit_op(OpCode::POP);
+            expression.right.compile(self);
+            self.patch_jump(end_jump);
+        }
+    }
+
+    pub fn visit_variable_expression(&mut self, expression: &mut VariableExpression) {
+        let name = expression.variable.clone();
+        self.named_variable(&name, false);
+    }
+
+    pub fn visit_assignment_expression(&mut self, expression: &mut AssignmentExpression) {
+        expression.value.compile(self);
+        let name = expression.name.clone();
+        self.named_variable(&name, true);
+    }
+
+    pub fn visit_call_expression(&mut self, expression: &mut CallExpression) {
+        expression.callee.compile(self);
+        for argument in &mut expression.arguments {
+            argument.compile(self);
+        }
+        self.line = expression.paren.line;
+        if expression.arguments.len() > u8::MAX as usize {
+            let paren = expression.paren.clone();
+            self.error("Can't have more than 255 arguments.", &paren);
+        }
+        self.emit_op(OpCode::CALL);
+        self.emit_byte(expression.arguments.len() as u8);
+    }
+
+    pub fn visit_get_expression(&mut self, expression: &mut GetExpression) {
+        expression.expression.compile(self);
+        let name = expression.name.clone();
+        self.line = name.line;
+        let constant = self.identifier_constant(&name);
+        self.emit_op(OpCode::GET_PROPERTY);
+        self.emit_short(constant);
+    }
+
+    pub fn visit_set_expression(&mut self, expression: &mut SetExpression) {
+        expression.expression.compile(self);
+        expression.value.compile(self);
+        let name = expression.name.clone();
+        self.line = name.line;
+        let constant = self.identifier_constant(&name);
+        self.emit_op(OpCode::SET_PROPERTY);
+        self.emit_short(constant);
+    }
+
+    pub fn visit_function_expression(&mut self, expression: &mut FunctionExpression) {
+        self.function(&mut expression.declaration, FunctionType::FUNCTION);
+    }
+
+    pub fn visit_list_expression(&mut self, expression: &mut ListExpression) {
+        for element in &mut expression.elements {
+            element.compile(self);
+        }
+        self.line = expression.bracket.line;
+        if expression.elements.len() > u16::MAX as usize {
+            let bracket = expression.bracket.clone();
+            self.error("Too many elements in a list literal.", &bracket);
+        }
+        self.emit_op(OpCode::BUILD_LIST);
+        self.emit_short(expression.elements.len() as u16);
+    }
+
+    pub fn visit_map_expression(&mut self, expression: &mut MapExpression) {
+        for (key, value) in &mut expression.entries {
+            key.compile(self);
+            value.compile(self);
+        }
+        self.line = expression.brace.line;
+        if expression.entries.len() > u16::MAX as usize {
+            let brace = expression.brace.clone();
+            self.error("Too many entries in a map literal.", &brace);
+        }
+        self.emit_op(OpCode::BUILD_MAP);
+        self.emit_short(expression.entries.len() as u16);
+    }
+
+    pub fn visit_index_expression(&mut self, expression: &mut IndexExpression) {
+        expression.expression.compile(self);
+        expression.index.compile(self);
+        self.line = expression.bracket.line;
+        self.emit_op(OpCode::GET_INDEX);
+    }
+
+    pub fn visit_index_set_expression(&mut self, expression: &mut IndexSetExpression) {
+        expression.expression.compile(self);
+        expression.index.compile(self);
+        expression.value.compile(self);
+        self.line = expression.bracket.line;
+        self.emit_op(OpCode::SET_INDEX);
+    }
+
+    pub fn visit_this_expression(&mut self, expression: &mut ThisExpression) {
+        let token = expression.value.clone();
+        self.named_variable(&token, false);
+    }
+
+    pub fn visit_super_expression(&mut self, expression: &mut SuperExpression) {
+        let keyword = expression.keyword.clone();
+        let method = expression.method.clone();
+        let this_token = Token {
+            token_value: String::from("this"),
+            ..keyword.clone()
+        };
+        self.named_variable(&this_token, false);
+        self.named_variable(&keyword, false);
+        self.line = method.line;
+        let constant = self.identifier_constant(&method);
+        self.emit_op(OpCode::GET_SUPER);
+        self.emit_short(constant);
+    }
+}
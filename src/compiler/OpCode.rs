@@ -0,0 +1,125 @@
+/// Instructions understood by the `Vm`. Operands follow the opcode byte in the
+/// chunk: constant indices are two bytes (big endian), local/upvalue slots and
+/// argument counts are a single byte, jump offsets are two bytes.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpCode {
+    CONSTANT,
+    NIL,
+    TRUE,
+    FALSE,
+    POP,
+    GET_LOCAL,
+    SET_LOCAL,
+    GET_GLOBAL,
+    DEFINE_GLOBAL,
+    SET_GLOBAL,
+    GET_UPVALUE,
+    SET_UPVALUE,
+    GET_PROPERTY,
+    SET_PROPERTY,
+    BUILD_LIST,
+    BUILD_MAP,
+    GET_INDEX,
+    SET_INDEX,
+    GET_SUPER,
+    EQUAL,
+    GREATER,
+    GREATER_EQUAL,
+    LESS,
+    LESS_EQUAL,
+    ADD,
+    SUBTRACT,
+    MULTIPLY,
+    DIVIDE,
+    NOT,
+    NEGATE,
+    PRINT,
+    JUMP,
+    JUMP_IF_FALSE,
+    LOOP,
+    CALL,
+    CLOSURE,
+    CLOSE_UPVALUE,
+    RETURN,
+    CLASS,
+    INHERIT,
+    METHOD,
+    STATIC_METHOD,
+    SETTER,
+    /// Installs an exception handler at the jump target. A runtime error
+    /// unwinds to it and pushes the caught value for the `catch` variable.
+    TRY,
+    /// Like `TRY`, but for a `finally` block: the error is set aside until
+    /// `RETHROW` resumes it.
+    TRY_FINALLY,
+    /// Removes the innermost exception handler.
+    END_TRY,
+    THROW,
+    RETHROW,
+    /// Forgets the error set aside by `TRY_FINALLY`, for a `finally` block
+    /// left by `return`, `break` or `continue`.
+    DROP_PENDING,
+    /// Loads the module at the path constant, the first operand, and pushes
+    /// it bound to the name constant, the second.
+    IMPORT,
+}
+
+const OPCODES: &[OpCode] = &[
+    OpCode::CONSTANT,
+    OpCode::NIL,
+    OpCode::TRUE,
+    OpCode::FALSE,
+    OpCode::POP,
+    OpCode::GET_LOCAL,
+    OpCode::SET_LOCAL,
+    OpCode::GET_GLOBAL,
+    OpCode::DEFINE_GLOBAL,
+    OpCode::SET_GLOBAL,
+    OpCode::GET_UPVALUE,
+    OpCode::SET_UPVALUE,
+    OpCode::GET_PROPERTY,
+    OpCode::SET_PROPERTY,
+    OpCode::BUILD_LIST,
+    OpCode::BUILD_MAP,
+    OpCode::GET_INDEX,
+    OpCode::SET_INDEX,
+    OpCode::GET_SUPER,
+    OpCode::EQUAL,
+    OpCode::GREATER,
+    OpCode::GREATER_EQUAL,
+    OpCode::LESS,
+    OpCode::LESS_EQUAL,
+    OpCode::ADD,
+    OpCode::SUBTRACT,
+    OpCode::MULTIPLY,
+    OpCode::DIVIDE,
+    OpCode::NOT,
+    OpCode::NEGATE,
+    OpCode::PRINT,
+    OpCode::JUMP,
+    OpCode::JUMP_IF_FALSE,
+    OpCode::LOOP,
+    OpCode::CALL,
+    OpCode::CLOSURE,
+    OpCode::CLOSE_UPVALUE,
+    OpCode::RETURN,
+    OpCode::CLASS,
+    OpCode::INHERIT,
+    OpCode::METHOD,
+    OpCode::STATIC_METHOD,
+    OpCode::SETTER,
+    OpCode::TRY,
+    OpCode::TRY_FINALLY,
+    OpCode::END_TRY,
+    OpCode::THROW,
+    OpCode::RETHROW,
+    OpCode::DROP_PENDING,
+    OpCode::IMPORT,
+];
+
+impl OpCode {
+    pub fn from_byte(byte: u8) -> OpCode {
+        OPCODES[byte as usize]
+    }
+}
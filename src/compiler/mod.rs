@@ -0,0 +1,3 @@
+pub mod Chunk;
+pub mod Compiler;
+pub mod OpCode;
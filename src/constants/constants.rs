@@ -2,28 +2,66 @@ use crate::enums::TokenType::TokenType;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
+/// How many Lox function calls can be running at once. Both runtimes fail
+/// with "Stack overflow." rather than go deeper.
+pub const MAX_CALL_DEPTH: usize = 4095;
+
+/// How many frames a traceback keeps at each end; deeper ones, such as
+/// runaway recursion, are summarised as `... N more`.
+pub const TRACEBACK_ENDS: usize = 10;
+
 pub static RESERVED_KEYWORDS: &[&str] = &[
-    "and", "class", "else", "false", "fun", "for", "if", "nil", "or", "print", "return", "super",
-    "this", "true", "var", "while",
+    "and", "break", "catch", "class", "continue", "else", "false", "finally", "fun", "for", "if",
+    "import", "nil", "or", "print", "return", "super", "this", "throw", "true", "try", "var",
+    "while",
 ];
 
 pub static RESERVED_KEYWORDS_MAP: Lazy<HashMap<&'static str, TokenType>> = Lazy::new(|| {
     let mut m = HashMap::new();
     m.insert("and", TokenType::AND);
+    m.insert("break", TokenType::BREAK);
+    m.insert("catch", TokenType::CATCH);
     m.insert("class", TokenType::CLASS);
+    m.insert("continue", TokenType::CONTINUE);
     m.insert("else", TokenType::ELSE);
     m.insert("false", TokenType::FALSE);
+    m.insert("finally", TokenType::FINALLY);
     m.insert("fun", TokenType::FUN);
     m.insert("for", TokenType::FOR);
     m.insert("if", TokenType::IF);
+    m.insert("import", TokenType::IMPORT);
     m.insert("nil", TokenType::NIL);
     m.insert("or", TokenType::OR);
     m.insert("print", TokenType::PRINT);
     m.insert("return", TokenType::RETURN);
     m.insert("super", TokenType::SUPER);
     m.insert("this", TokenType::THIS);
+    m.insert("throw", TokenType::THROW);
     m.insert("true", TokenType::TRUE);
+    m.insert("try", TokenType::TRY);
     m.insert("var", TokenType::VAR);
     m.insert("while", TokenType::WHILE);
     m
 });
+
+/// The method a class defines to overload a binary operator, by the
+/// operator's lexeme. `!=` negates the result of `__eq__`.
+pub static OPERATOR_METHODS: &[(&str, &str)] = &[
+    ("+", "__add__"),
+    ("-", "__sub__"),
+    ("*", "__mul__"),
+    ("/", "__div__"),
+    ("<", "__lt__"),
+    ("<=", "__le__"),
+    (">", "__gt__"),
+    (">=", "__ge__"),
+    ("==", "__eq__"),
+    ("!=", "__eq__"),
+];
+
+pub fn operator_method(operator: &str) -> Option<&'static str> {
+    OPERATOR_METHODS
+        .iter()
+        .find(|(lexeme, _)| *lexeme == operator)
+        .map(|(_, method)| *method)
+}
@@ -0,0 +1,12 @@
+use super::LiteralValue::LiteralValue;
+
+/// How a statement finished running. Anything other than `NORMAL` unwinds
+/// the enclosing blocks until a loop (`BREAK`, `CONTINUE`) or a function
+/// call (`RETURN`) handles it.
+#[derive(Clone)]
+pub enum ControlFlow {
+    NORMAL,
+    BREAK,
+    CONTINUE,
+    RETURN(LiteralValue),
+}
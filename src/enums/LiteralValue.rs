@@ -1,20 +1,240 @@
 use std::{cell::RefCell, fmt, rc::Rc};
 
-use crate::implementation::{
-    Clock::Clock, LoxClass::LoxClass, LoxFunction::LoxFunction, LoxInstance::LoxInstance,
+use crate::{
+    enums::MapKey::MapKey,
+    implementation::{
+        ListMethod::{ListMethod, ListRef},
+        LoxClass::LoxClass,
+        LoxFunction::LoxFunction,
+        LoxInstance::LoxInstance,
+        MapMethod::{MapMethod, MapRef},
+        Module::LoxModule,
+        NativeFunction::NativeFunction,
+    },
 };
 
+/// Gives the text of an instance inside a printed list or map.
+pub type InstanceText<'a, E> = dyn FnMut(&LiteralValue) -> Result<String, E> + 'a;
+
 #[derive(Clone, Default)]
 pub enum LiteralValue {
     #[default]
     Nil,
     Boolean(bool),
-    Number(String),
+    Number(f64),
     String(String),
-    Clock(Clock),
+    Native(NativeFunction),
     Function(LoxFunction),
     LoxClass(LoxClass),
     Instance(Rc<RefCell<LoxInstance>>),
+    List(ListRef),
+    ListMethod(ListMethod),
+    Map(MapRef),
+    MapMethod(MapMethod),
+    Module(Rc<LoxModule>),
+}
+
+impl LiteralValue {
+    /// The name of the value's Lox type, for error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            LiteralValue::Nil => "nil",
+            LiteralValue::Boolean(_) => "boolean",
+            LiteralValue::Number(_) => "number",
+            LiteralValue::String(_) => "string",
+            LiteralValue::Function(_)
+            | LiteralValue::Native(_)
+            | LiteralValue::ListMethod(_)
+            | LiteralValue::MapMethod(_) => "function",
+            LiteralValue::LoxClass(_) => "class",
+            LiteralValue::Instance(_) => "instance",
+            LiteralValue::List(_) => "list",
+            LiteralValue::Map(_) => "map",
+            LiteralValue::Module(_) => "module",
+        }
+    }
+
+    /// The text of a list or map, with `instance` giving the text of each
+    /// instance inside it so the interpreter can run `toString()`. Other
+    /// values show their `Display` text.
+    pub fn fmt_collection<E>(&self, instance: &mut InstanceText<E>) -> Result<String, E> {
+        let mut out = String::new();
+        match self {
+            LiteralValue::List(list) => {
+                LiteralValue::fmt_list(list, &mut out, &mut Vec::new(), instance)?
+            }
+            LiteralValue::Map(map) => {
+                LiteralValue::fmt_map(map, &mut out, &mut Vec::new(), instance)?
+            }
+            other => out.push_str(&other.to_string()),
+        }
+        Ok(out)
+    }
+
+    /// How a value looks inside a list or map: numbers as `print` shows them
+    /// and strings quoted. `seen` holds the collections being printed further
+    /// up, so one that contains itself prints as `[...]` / `{...}` instead of
+    /// recursing.
+    fn fmt_element<E>(
+        &self,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        match self {
+            LiteralValue::Number(num) => out.push_str(&num.to_string()),
+            LiteralValue::String(s) => out.push_str(&format!("\"{}\"", s)),
+            LiteralValue::List(list) => LiteralValue::fmt_list(list, out, seen, instance)?,
+            LiteralValue::Map(map) => LiteralValue::fmt_map(map, out, seen, instance)?,
+            LiteralValue::Instance(_) => out.push_str(&instance(self)?),
+            other => out.push_str(&other.to_string()),
+        }
+        Ok(())
+    }
+
+    fn fmt_list<E>(
+        list: &ListRef,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        let ptr = Rc::as_ptr(list) as *const ();
+        if seen.contains(&ptr) {
+            out.push_str("[...]");
+            return Ok(());
+        }
+        seen.push(ptr);
+        out.push('[');
+        // A copy, so a `toString()` that changes the list can't clash with
+        // the borrow.
+        let elements = list.borrow().clone();
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            element.fmt_element(out, seen, instance)?;
+        }
+        seen.pop();
+        out.push(']');
+        Ok(())
+    }
+
+    fn fmt_map<E>(
+        map: &MapRef,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        let ptr = Rc::as_ptr(map) as *const ();
+        if seen.contains(&ptr) {
+            out.push_str("{...}");
+            return Ok(());
+        }
+        seen.push(ptr);
+        out.push('{');
+        let entries = map.borrow().clone();
+        for (i, (key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            match key {
+                MapKey::Instance(_) => key.to_literal().fmt_element(out, seen, instance)?,
+                key => out.push_str(&key.to_string()),
+            }
+            out.push_str(": ");
+            value.fmt_element(out, seen, instance)?;
+        }
+        seen.pop();
+        out.push('}');
+        Ok(())
+    }
+}
+
+/// Structural equality: lists and maps compare by contents, and a
+/// collection that contains itself compares equal to one of the same shape.
+/// Instances, classes, functions and modules compare by identity.
+impl PartialEq for LiteralValue {
+    fn eq(&self, other: &LiteralValue) -> bool {
+        self.equals(other, &mut Vec::new())
+    }
+}
+
+impl LiteralValue {
+    /// `==` itself. `seen` holds the pairs of collections being compared
+    /// further up; meeting a pair again means both sides went round a cycle
+    /// in step without a difference, so it counts as equal.
+    fn equals(&self, other: &LiteralValue, seen: &mut Vec<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (LiteralValue::Nil, LiteralValue::Nil) => true,
+            (LiteralValue::Boolean(left), LiteralValue::Boolean(right)) => left == right,
+            (LiteralValue::Number(left), LiteralValue::Number(right)) => left == right,
+            (LiteralValue::String(left), LiteralValue::String(right)) => left == right,
+            (LiteralValue::List(left), LiteralValue::List(right)) => {
+                LiteralValue::collections_equal(left, right, seen, |left, right, seen| {
+                    let (left, right) = (left.borrow(), right.borrow());
+                    left.len() == right.len()
+                        && left
+                            .iter()
+                            .zip(right.iter())
+                            .all(|(l, r)| l.equals(r, seen))
+                })
+            }
+            (LiteralValue::Map(left), LiteralValue::Map(right)) => {
+                LiteralValue::collections_equal(left, right, seen, |left, right, seen| {
+                    let (left, right) = (left.borrow(), right.borrow());
+                    left.len() == right.len()
+                        && left
+                            .iter()
+                            .all(|(key, l)| right.get(key).is_some_and(|r| l.equals(r, seen)))
+                })
+            }
+            // Each import of a file gets its own value, named after its
+            // alias, but they all share the file's globals.
+            (LiteralValue::Module(left), LiteralValue::Module(right)) => {
+                Rc::ptr_eq(&left.globals, &right.globals)
+            }
+            (LiteralValue::Instance(left), LiteralValue::Instance(right)) => {
+                Rc::ptr_eq(left, right)
+            }
+            // Copies of a class share the static fields map created with it,
+            // so that map identifies the class.
+            (LiteralValue::LoxClass(left), LiteralValue::LoxClass(right)) => {
+                Rc::ptr_eq(&left.fields, &right.fields)
+            }
+            // A function value is one declaration closed over one scope.
+            // Binding a method makes a new scope, so each `object.method`
+            // read is a distinct value.
+            (LiteralValue::Function(left), LiteralValue::Function(right)) => {
+                Rc::ptr_eq(&left.closure, &right.closure)
+                    && left.declaration.name.span == right.declaration.name.span
+            }
+            (LiteralValue::Native(left), LiteralValue::Native(right)) => {
+                Rc::ptr_eq(&left.function, &right.function)
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs `contents_equal` on two lists or two maps unless they are the
+    /// same collection or a pair already being compared.
+    fn collections_equal<C>(
+        left: &Rc<C>,
+        right: &Rc<C>,
+        seen: &mut Vec<(*const (), *const ())>,
+        contents_equal: impl FnOnce(&Rc<C>, &Rc<C>, &mut Vec<(*const (), *const ())>) -> bool,
+    ) -> bool {
+        let pair = (
+            Rc::as_ptr(left) as *const (),
+            Rc::as_ptr(right) as *const (),
+        );
+        if Rc::ptr_eq(left, right) || seen.contains(&pair) {
+            return true;
+        }
+        seen.push(pair);
+        let equal = contents_equal(left, right, seen);
+        seen.pop();
+        equal
+    }
 }
 
 impl fmt::Display for LiteralValue {
@@ -23,31 +243,33 @@ impl fmt::Display for LiteralValue {
             LiteralValue::Boolean(val) => write!(f, "{}", val),
             LiteralValue::Nil => write!(f, "nil"),
             LiteralValue::String(s) => write!(f, "{}", s),
-            LiteralValue::Clock(_) => write!(f, "<native fn>"),
+            LiteralValue::Native(_) | LiteralValue::ListMethod(_) | LiteralValue::MapMethod(_) => {
+                write!(f, "<native fn>")
+            }
+            LiteralValue::List(_) | LiteralValue::Map(_) => {
+                let text = self.fmt_collection(&mut |instance| Ok(instance.to_string()))?;
+                write!(f, "{}", text)
+            }
             LiteralValue::Function(lf) => {
                 write!(f, "<fn {}>", lf.declaration.name.token_value.to_string())
             }
             LiteralValue::LoxClass(cl) => {
                 write!(f, "{}", cl.name)
             }
+            LiteralValue::Module(module) => write!(f, "<module {}>", module.name),
             LiteralValue::Instance(rc_inst) => {
                 let inst = rc_inst.borrow();
                 write!(f, "{} instance", inst.klass.name)
             }
-            LiteralValue::Number(s) => {
-                if let Ok(num) = s.parse::<f64>() {
-                    // Using default formatting gives us the trimmed version.
-                    let formatted = format!("{}", num);
-                    // If the default formatted string doesn't contain a '.', it means it's whole,
-                    // so we append ".0". Otherwise, we output the formatted string as is.
-                    if formatted.contains('.') {
-                        write!(f, "{}", formatted)
-                    } else {
-                        write!(f, "{}.0", formatted)
-                    }
+            LiteralValue::Number(num) => {
+                // Using default formatting gives us the trimmed version.
+                let formatted = format!("{}", num);
+                // If the default formatted string doesn't contain a '.', it means it's whole,
+                // so we append ".0". Otherwise, we output the formatted string as is.
+                if formatted.contains('.') || !num.is_finite() {
+                    write!(f, "{}", formatted)
                 } else {
-                    // Fallback: if parsing fails, simply print the original string.
-                    write!(f, "{}", s)
+                    write!(f, "{}.0", formatted)
                 }
             }
         }
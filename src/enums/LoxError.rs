@@ -0,0 +1,193 @@
+use thiserror::Error;
+
+use crate::{
+    constants::constants::TRACEBACK_ENDS,
+    enums::TokenType::TokenType,
+    implementation::Token::{Span, Token},
+};
+
+/// Every error the front end or the runtimes can produce. Nothing is printed
+/// when one is created; callers decide how to report it.
+#[derive(Debug, Clone, Error)]
+pub enum LoxError {
+    #[error("[line {line}] Error: {message}")]
+    Lex {
+        line: u32,
+        span: Span,
+        message: String,
+        note: Option<String>,
+    },
+    #[error("[line {line}] Error{}: {message}", location(.token))]
+    Parse {
+        token: Box<Token>,
+        line: u32,
+        message: String,
+        note: Option<String>,
+    },
+    #[error("{message}\n[line {line}]")]
+    Resolve {
+        token: Box<Token>,
+        line: u32,
+        message: String,
+        note: Option<String>,
+    },
+    /// `token` is `None` for errors raised by the bytecode VM, which only
+    /// keeps line information and the id of the `file` the code came from.
+    /// `trace` lists the functions the error unwound through, innermost
+    /// first, each with the line it was called from.
+    #[error("{message}\n[line {line}]")]
+    Runtime {
+        token: Option<Box<Token>>,
+        line: u32,
+        file: u32,
+        message: String,
+        note: Option<String>,
+        trace: Vec<(String, u32)>,
+    },
+}
+
+fn location(token: &Token) -> String {
+    match token.token_type {
+        TokenType::EOF => String::from(" at end"),
+        _ => format!(" at '{}'", token.token_value),
+    }
+}
+
+impl LoxError {
+    pub fn lex(line: u32, span: Span, message: String) -> LoxError {
+        LoxError::Lex {
+            line,
+            span,
+            message,
+            note: None,
+        }
+    }
+
+    pub fn parse(token: &Token, message: String) -> LoxError {
+        LoxError::Parse {
+            token: Box::new(token.clone()),
+            line: token.line,
+            message,
+            note: None,
+        }
+    }
+
+    pub fn resolve(token: &Token, message: String) -> LoxError {
+        LoxError::Resolve {
+            token: Box::new(token.clone()),
+            line: token.line,
+            message,
+            note: None,
+        }
+    }
+
+    pub fn runtime(token: &Token, message: String) -> LoxError {
+        LoxError::Runtime {
+            token: Some(Box::new(token.clone())),
+            line: token.line,
+            file: token.span.file,
+            message,
+            note: None,
+            trace: Vec::new(),
+        }
+    }
+
+    pub fn line(&self) -> u32 {
+        match self {
+            LoxError::Lex { line, .. }
+            | LoxError::Parse { line, .. }
+            | LoxError::Resolve { line, .. }
+            | LoxError::Runtime { line, .. } => *line,
+        }
+    }
+
+    /// The source span the error points at. Errors from the bytecode VM
+    /// carry no token and so have no span.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            LoxError::Lex { span, .. } => Some(*span),
+            LoxError::Parse { token, .. } | LoxError::Resolve { token, .. } => Some(token.span),
+            LoxError::Runtime { token, .. } => token.as_ref().map(|token| token.span),
+        }
+    }
+
+    /// The id of the file the error was raised in; see [`Span::file`].
+    pub fn file(&self) -> u32 {
+        match self {
+            LoxError::Lex { span, .. } => span.file,
+            LoxError::Parse { token, .. } | LoxError::Resolve { token, .. } => token.span.file,
+            LoxError::Runtime { file, .. } => *file,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            LoxError::Lex { message, .. }
+            | LoxError::Parse { message, .. }
+            | LoxError::Resolve { message, .. }
+            | LoxError::Runtime { message, .. } => message,
+        }
+    }
+
+    /// Attaches a hint that rich diagnostics print below the snippet.
+    pub fn with_note(mut self, text: &str) -> LoxError {
+        match &mut self {
+            LoxError::Lex { note, .. }
+            | LoxError::Parse { note, .. }
+            | LoxError::Resolve { note, .. }
+            | LoxError::Runtime { note, .. } => *note = Some(text.to_string()),
+        }
+        self
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        match self {
+            LoxError::Lex { note, .. }
+            | LoxError::Parse { note, .. }
+            | LoxError::Resolve { note, .. }
+            | LoxError::Runtime { note, .. } => note.as_deref(),
+        }
+    }
+
+    /// Records that a runtime error escaped from `function`, which was called
+    /// on `call_line`. Other errors are returned unchanged.
+    pub fn with_frame(mut self, function: &str, call_line: u32) -> LoxError {
+        if let LoxError::Runtime { trace, .. } = &mut self {
+            trace.push((function.to_string(), call_line));
+        }
+        self
+    }
+
+    /// Traceback lines such as `at fib (line 3)`, innermost call first and
+    /// ending with `at <script> (line N)`. Empty unless the error was raised
+    /// inside a function. Long traces keep only their innermost and
+    /// outermost frames.
+    pub fn traceback(&self) -> Vec<String> {
+        let (trace, mut line) = match self {
+            LoxError::Runtime { trace, line, .. } if !trace.is_empty() => (trace, *line),
+            _ => return Vec::new(),
+        };
+        let mut frames = Vec::new();
+        for (function, call_line) in trace {
+            frames.push(format!("at {} (line {})", function, line));
+            line = *call_line;
+        }
+        frames.push(format!("at <script> (line {})", line));
+        if frames.len() > TRACEBACK_ENDS * 2 {
+            let skipped = frames.len() - TRACEBACK_ENDS * 2;
+            frames.splice(
+                TRACEBACK_ENDS..frames.len() - TRACEBACK_ENDS,
+                [format!("... {} more", skipped)],
+            );
+        }
+        frames
+    }
+
+    /// The conventional sysexits code: 65 for static errors, 70 for runtime ones.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LoxError::Runtime { .. } => 70,
+            _ => 65,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    any::Any,
+    fmt,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
+
+use crate::{enums::LiteralValue::LiteralValue, implementation::Token::Span};
+
+/// The hashable subset of Lox values that can be used as map keys. Numbers
+/// are stored by their bits with `-0` folded into `0` and every NaN folded
+/// into one, so keys that print the same hash the same.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Nil,
+    Boolean(bool),
+    Number(u64),
+    String(String),
+    Instance(ObjectKey),
+    Class(ObjectKey),
+    Function(ObjectKey),
+}
+
+/// An instance, class or function used as a map key. It hashes and compares
+/// by the same identity `==` uses and keeps the value alive so `keys()` can
+/// hand it back. Either runtime's values can be stored; each only reads back
+/// its own.
+#[derive(Clone)]
+pub struct ObjectKey {
+    value: Rc<dyn Any>,
+    address: usize,
+    /// Tells apart tree-walker functions declared in the same scope, which
+    /// share a closure. Left as the default everywhere else.
+    span: Span,
+    /// How the key prints inside a map, fixed when the key is made.
+    label: String,
+}
+
+impl ObjectKey {
+    pub fn new<T: 'static>(value: T, address: usize, span: Span, label: String) -> ObjectKey {
+        ObjectKey {
+            value: Rc::new(value),
+            address,
+            span,
+            label,
+        }
+    }
+
+    /// An instance key, identified by the instance's own allocation.
+    pub fn instance<T: 'static>(instance: &Rc<T>, label: String) -> ObjectKey {
+        let address = Rc::as_ptr(instance) as *const () as usize;
+        ObjectKey::new(Rc::clone(instance), address, Span::default(), label)
+    }
+
+    pub fn value<T: Clone + 'static>(&self) -> Option<T> {
+        self.value.downcast_ref::<T>().cloned()
+    }
+}
+
+impl PartialEq for ObjectKey {
+    fn eq(&self, other: &ObjectKey) -> bool {
+        self.address == other.address && self.span == other.span
+    }
+}
+
+impl Eq for ObjectKey {}
+
+impl Hash for ObjectKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+        self.span.hash(state);
+    }
+}
+
+impl fmt::Debug for ObjectKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {:#x}", self.label, self.address)
+    }
+}
+
+impl MapKey {
+    pub fn from_number(num: f64) -> MapKey {
+        let canonical = if num == 0.0 {
+            0.0
+        } else if num.is_nan() {
+            f64::NAN
+        } else {
+            num
+        };
+        MapKey::Number(canonical.to_bits())
+    }
+
+    /// Returns `None` for values that can't be hashed (lists, maps and
+    /// bound list and map methods). Instances, classes and functions are
+    /// keyed by identity.
+    pub fn from_literal(value: &LiteralValue) -> Option<MapKey> {
+        let key = |address: *const (), span: Span| {
+            ObjectKey::new(value.clone(), address as usize, span, value.to_string())
+        };
+        match value {
+            LiteralValue::Nil => Some(MapKey::Nil),
+            LiteralValue::Boolean(val) => Some(MapKey::Boolean(*val)),
+            LiteralValue::Number(num) => Some(MapKey::from_number(*num)),
+            LiteralValue::String(s) => Some(MapKey::String(s.clone())),
+            LiteralValue::Instance(instance) => Some(MapKey::Instance(ObjectKey::instance(
+                instance,
+                value.to_string(),
+            ))),
+            // The same identities `==` compares; see `LiteralValue::equals`.
+            LiteralValue::LoxClass(class) => Some(MapKey::Class(key(
+                Rc::as_ptr(&class.fields) as *const (),
+                Span::default(),
+            ))),
+            LiteralValue::Function(function) => Some(MapKey::Function(key(
+                Rc::as_ptr(&function.closure) as *const (),
+                function.declaration.name.span,
+            ))),
+            LiteralValue::Native(native) => Some(MapKey::Function(key(
+                Rc::as_ptr(&native.function) as *const (),
+                Span::default(),
+            ))),
+            _ => None,
+        }
+    }
+
+    pub fn to_literal(&self) -> LiteralValue {
+        match self {
+            MapKey::Nil => LiteralValue::Nil,
+            MapKey::Boolean(val) => LiteralValue::Boolean(*val),
+            MapKey::Number(bits) => LiteralValue::Number(f64::from_bits(*bits)),
+            MapKey::String(s) => LiteralValue::String(s.clone()),
+            MapKey::Instance(key) => LiteralValue::Instance(
+                key.value()
+                    .expect("tree-walker maps only hold tree-walker instances"),
+            ),
+            MapKey::Class(key) | MapKey::Function(key) => key
+                .value()
+                .expect("tree-walker maps only hold tree-walker values"),
+        }
+    }
+}
+
+/// Formats a key the way it appears inside a printed map.
+impl fmt::Display for MapKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapKey::Nil => write!(f, "nil"),
+            MapKey::Boolean(val) => write!(f, "{}", val),
+            MapKey::Number(bits) => write!(f, "{}", f64::from_bits(*bits)),
+            MapKey::String(s) => write!(f, "\"{}\"", s),
+            MapKey::Instance(key) | MapKey::Class(key) | MapKey::Function(key) => {
+                write!(f, "{}", key.label)
+            }
+        }
+    }
+}
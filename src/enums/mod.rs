@@ -1,2 +1,5 @@
+pub mod ControlFlow;
 pub mod LiteralValue;
+pub mod LoxError;
+pub mod MapKey;
 pub mod TokenType;
@@ -1,4 +1,5 @@
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::Token::Token;
 use crate::traits::Expression::Expression;
 use crate::traits::Interpreter::InterpreterTrait;
@@ -21,8 +22,12 @@ impl Clone for AssignmentExpression {
 }
 
 impl Expression for AssignmentExpression {
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_assignment_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_assignment_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_assignment_expression(self);
     }
 
     fn id(&self) -> u32 {
@@ -35,7 +40,7 @@ impl Expression for AssignmentExpression {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_assignment_expression(self);
     }
 
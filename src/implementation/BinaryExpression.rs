@@ -1,4 +1,5 @@
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AstPrinter::AstPrinter;
 use crate::implementation::Token::Token;
 use crate::traits::AstPrinter::AstPrinterTrait;
@@ -35,15 +36,19 @@ impl Expression for BinaryExpression {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_binary_expression(self);
     }
     fn as_any(&self) -> &dyn Any {
         self
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_binary_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_binary_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_binary_expression(self);
     }
 
     fn clone_box(&self) -> Box<dyn Expression> {
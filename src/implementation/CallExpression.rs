@@ -1,4 +1,5 @@
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AstPrinter::AstPrinter;
 use crate::implementation::Token::Token;
 use crate::traits::Expression::Expression;
@@ -34,15 +35,19 @@ impl Expression for CallExpression {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_call_expression(self);
     }
     fn as_any(&self) -> &dyn Any {
         self
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_call_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_call_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_call_expression(self);
     }
 
     fn clone_box(&self) -> Box<dyn Expression> {
@@ -1,12 +1,15 @@
 use std::any::Any;
 
-use crate::{enums::LiteralValue::LiteralValue, traits::Statement::Statement};
+use crate::{enums::{ControlFlow::ControlFlow, LoxError::LoxError}, traits::Statement::Statement};
 
 use super::{Token::Token, VariableExpression::VariableExpression};
 
 pub struct ClassStatement {
     pub name: Token,
     pub methods: Vec<Box<dyn Statement>>,
+    pub static_methods: Vec<Box<dyn Statement>>,
+    /// Methods declared `set name(value)`, called when the property is assigned.
+    pub setters: Vec<Box<dyn Statement>>,
     pub super_class: Option<VariableExpression>,
 }
 
@@ -18,16 +21,26 @@ impl Statement for ClassStatement {
         Box::new(ClassStatement {
             name: self.name.clone(),
             methods: self.methods.iter().map(|stmt| stmt.clone_box()).collect(),
+            static_methods: self
+                .static_methods
+                .iter()
+                .map(|stmt| stmt.clone_box())
+                .collect(),
+            setters: self.setters.iter().map(|stmt| stmt.clone_box()).collect(),
             super_class: self.super_class.clone(),
         })
     }
     fn interpret(
         &mut self,
         interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return interpreter.visit_class_statement(self);
     }
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_class_statement(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_class_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_class_statement(self);
     }
 }
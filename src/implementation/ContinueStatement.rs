@@ -0,0 +1,37 @@
+use std::any::Any;
+
+use crate::{
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, Statement::Statement},
+};
+
+use super::Token::Token;
+
+#[derive(Clone)]
+pub struct ContinueStatement {
+    pub keyword: Token,
+}
+
+impl Statement for ContinueStatement {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn InterpreterTrait,
+    ) -> Result<ControlFlow, LoxError> {
+        return interpreter.visit_continue_statement(self);
+    }
+
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_continue_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_continue_statement(self);
+    }
+
+    fn clone_box(&self) -> Box<dyn Statement> {
+        Box::new(self.clone())
+    }
+}
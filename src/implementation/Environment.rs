@@ -1,6 +1,6 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::{LiteralValue::LiteralValue, LoxError::LoxError};
 
 use super::Token::Token;
 
@@ -13,7 +13,7 @@ pub struct Environment {
 pub trait EnvExt {
     fn ancestor(&self, distance: usize) -> Rc<RefCell<Environment>>;
     fn get_at(&self, distance: usize, name: &str) -> Option<LiteralValue>;
-    fn assign_at(&self, distance: usize, token: Token, value: LiteralValue) -> Result<(), String>;
+    fn assign_at(&self, distance: usize, token: Token, value: LiteralValue) -> Result<(), LoxError>;
 }
 
 impl EnvExt for Rc<RefCell<Environment>> {
@@ -33,7 +33,7 @@ impl EnvExt for Rc<RefCell<Environment>> {
         env
     }
 
-    fn assign_at(&self, distance: usize, token: Token, value: LiteralValue) -> Result<(), String> {
+    fn assign_at(&self, distance: usize, token: Token, value: LiteralValue) -> Result<(), LoxError> {
         let _res = self
             .ancestor(distance)
             .borrow_mut()
@@ -68,7 +68,7 @@ impl Environment {
             None
         }
     }
-    pub fn assign(&mut self, token: Token, value: LiteralValue) -> Result<(), String> {
+    pub fn assign(&mut self, token: Token, value: LiteralValue) -> Result<(), LoxError> {
         if self.values.contains_key(&token.token_value) {
             self.values.insert(token.token_value, value);
             return Ok(());
@@ -80,9 +80,9 @@ impl Environment {
             }
             None => {}
         }
-        return Err(String::from(format!(
-            "Undefined variable {}.",
-            token.token_value
-        )));
+        return Err(LoxError::runtime(
+            &token,
+            format!("Undefined variable {}.", token.token_value),
+        ));
     }
 }
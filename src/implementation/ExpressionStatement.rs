@@ -1,7 +1,7 @@
 use std::any::Any;
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
     traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
 };
 
@@ -24,11 +24,15 @@ impl Statement for ExpressionStatement {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return interpreter.visit_expression_statement(self);
     }
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_expression_statement(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_expression_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_expression_statement(self);
     }
 
     fn clone_box(&self) -> Box<dyn Statement> {
@@ -0,0 +1,49 @@
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::Expression::Expression,
+};
+
+use super::{FunctionStatement::FunctionStatement, Token::Token};
+
+/// An anonymous function such as `fun (a, b) { return a + b; }`. The
+/// declaration's name is the placeholder `anonymous`, which is never bound.
+#[derive(Clone)]
+pub struct FunctionExpression {
+    pub keyword: Token,
+    pub declaration: FunctionStatement,
+    pub id: u32,
+}
+
+impl Expression for FunctionExpression {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn Expression> {
+        Box::new(self.clone())
+    }
+    fn expression_print(&self) -> String {
+        let parameters: Vec<String> = self
+            .declaration
+            .parameters
+            .iter()
+            .map(|parameter| parameter.token_value.clone())
+            .collect();
+        format!("(fun ({}))", parameters.join(" "))
+    }
+    fn id(&self) -> u32 {
+        self.id
+    }
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_function_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_function_expression(self);
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
+    ) -> Result<LiteralValue, LoxError> {
+        return interpreter.visit_function_expression(self);
+    }
+}
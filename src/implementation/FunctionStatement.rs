@@ -1,7 +1,7 @@
 use std::any::Any;
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
     traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
 };
 
@@ -11,6 +11,9 @@ pub struct FunctionStatement {
     pub name: Token,
     pub parameters: Vec<Token>,
     pub body: Vec<Box<dyn Statement>>,
+    /// Declared without a parameter list, as `area { ... }`. Reading the
+    /// property calls it instead of returning the bound method.
+    pub is_getter: bool,
 }
 
 impl Clone for FunctionStatement {
@@ -19,6 +22,7 @@ impl Clone for FunctionStatement {
             name: self.name.clone(),
             parameters: self.parameters.clone(),
             body: self.body.iter().map(|stmt| stmt.clone_box()).collect(),
+            is_getter: self.is_getter,
         }
     }
 }
@@ -30,12 +34,16 @@ impl Statement for FunctionStatement {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return interpreter.visit_function_statement(self);
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_function_statement(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_function_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_function_statement(self);
     }
 
     fn clone_box(&self) -> Box<dyn Statement> {
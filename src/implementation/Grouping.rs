@@ -1,6 +1,7 @@
 use std::any::Any;
 
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AstPrinter::AstPrinter;
 use crate::traits::AstPrinter::AstPrinterTrait;
 use crate::traits::Expression::Expression;
@@ -32,14 +33,18 @@ impl Expression for Grouping {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_grouping(self);
     }
     fn as_any(&self) -> &dyn Any {
         self
     }
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_grouping_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_grouping_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_grouping_expression(self);
     }
 
     fn clone_box(&self) -> Box<dyn Expression> {
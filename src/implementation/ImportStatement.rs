@@ -0,0 +1,42 @@
+use std::any::Any;
+
+use crate::{
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, Statement::Statement},
+};
+
+use super::Token::Token;
+
+/// `import "path.lox";` or `import "path.lox" as name;`. Without `as` the
+/// module is bound to its file name minus the extension.
+#[derive(Clone)]
+pub struct ImportStatement {
+    pub keyword: Token,
+    /// The path string literal, relative to the importing file.
+    pub path: Token,
+    pub name: Token,
+}
+
+impl Statement for ImportStatement {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn InterpreterTrait,
+    ) -> Result<ControlFlow, LoxError> {
+        return interpreter.visit_import_statement(self);
+    }
+
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_import_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_import_statement(self);
+    }
+
+    fn clone_box(&self) -> Box<dyn Statement> {
+        Box::new(self.clone())
+    }
+}
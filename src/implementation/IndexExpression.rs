@@ -0,0 +1,53 @@
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{AstPrinter::AstPrinterTrait, Expression::Expression},
+};
+
+use super::{AstPrinter::AstPrinter, Token::Token};
+
+/// Reads an element: `xs[i]`. `bracket` is the closing `]`, used for errors.
+pub struct IndexExpression {
+    pub expression: Box<dyn Expression>,
+    pub bracket: Token,
+    pub index: Box<dyn Expression>,
+    pub id: u32,
+}
+
+impl Clone for IndexExpression {
+    fn clone(&self) -> Self {
+        IndexExpression {
+            expression: self.expression.clone_box(),
+            bracket: self.bracket.clone(),
+            index: self.index.clone_box(),
+            id: self.id,
+        }
+    }
+}
+
+impl Expression for IndexExpression {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn Expression> {
+        Box::new(self.clone())
+    }
+    fn expression_print(&self) -> String {
+        AstPrinter {}.paranthesize(String::from("index"), &vec![&self.expression, &self.index])
+    }
+    fn id(&self) -> u32 {
+        self.id
+    }
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_index_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_index_expression(self);
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
+    ) -> Result<LiteralValue, LoxError> {
+        return interpreter.visit_index_expression(self);
+    }
+}
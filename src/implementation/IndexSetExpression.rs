@@ -0,0 +1,58 @@
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{AstPrinter::AstPrinterTrait, Expression::Expression},
+};
+
+use super::{AstPrinter::AstPrinter, Token::Token};
+
+/// Writes an element: `xs[i] = value`.
+pub struct IndexSetExpression {
+    pub expression: Box<dyn Expression>,
+    pub bracket: Token,
+    pub index: Box<dyn Expression>,
+    pub value: Box<dyn Expression>,
+    pub id: u32,
+}
+
+impl Clone for IndexSetExpression {
+    fn clone(&self) -> Self {
+        IndexSetExpression {
+            expression: self.expression.clone_box(),
+            bracket: self.bracket.clone(),
+            index: self.index.clone_box(),
+            value: self.value.clone_box(),
+            id: self.id,
+        }
+    }
+}
+
+impl Expression for IndexSetExpression {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn Expression> {
+        Box::new(self.clone())
+    }
+    fn expression_print(&self) -> String {
+        AstPrinter {}.paranthesize(
+            String::from("index="),
+            &vec![&self.expression, &self.index, &self.value],
+        )
+    }
+    fn id(&self) -> u32 {
+        self.id
+    }
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_index_set_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_index_set_expression(self);
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
+    ) -> Result<LiteralValue, LoxError> {
+        return interpreter.visit_index_set_expression(self);
+    }
+}
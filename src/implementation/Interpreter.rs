@@ -1,35 +1,47 @@
 use core::panic;
 
 use std::{
+    any::Any,
     boxed,
     cell::RefCell,
     collections::HashMap,
     f32::consts::E,
     hash::Hash,
-    io::{self, Write},
-    process::exit,
     rc::Rc,
 };
 
+use indexmap::IndexMap;
+
 use crate::implementation::Environment::EnvExt;
-use crate::implementation::LoxInstance::LoxInstanceExt;
+use crate::implementation::LoxInstance::{LoxInstance, LoxInstanceExt};
 
 use crate::{
-    enums::{LiteralValue::LiteralValue, TokenType::TokenType},
+    constants::constants::{operator_method, MAX_CALL_DEPTH},
+    enums::{
+        ControlFlow::ControlFlow, LiteralValue::LiteralValue, LoxError::LoxError,
+        TokenType::TokenType,
+    },
     traits::{
         Expression::Expression, Interpreter::InterpreterTrait, LoxCallableTrait::LoxCallableTrait,
-        Statement::Statement,
+        LoxConvert::IntoNativeResult, Statement::Statement,
     },
 };
 
 use super::{
     AssignmentExpression::AssignmentExpression, BinaryExpression::BinaryExpression,
-    BlockStatement::BlockStatement, CallExpression::CallExpression, Clock::Clock,
+    BlockStatement::BlockStatement, BreakStatement::BreakStatement, CallExpression::CallExpression,
+    ContinueStatement::ContinueStatement,
     Environment::Environment, ExpressionStatement::ExpressionStatement,
-    FunctionStatement::FunctionStatement, GetExpression::GetExpression, Grouping::Grouping,
-    IfStatement::IfStatement, Literal::Literal, LoxClass::LoxClass, LoxFunction::LoxFunction,
+    FunctionExpression::FunctionExpression, FunctionStatement::FunctionStatement, GetExpression::GetExpression, Grouping::Grouping,
+    IfStatement::IfStatement, ImportStatement::ImportStatement, IndexExpression::IndexExpression,
+    IndexSetExpression::IndexSetExpression, ListExpression::ListExpression,
+    ListMethod::{list_index, ListMethod}, Literal::Literal, LoxClass::LoxClass,
+    LoxFunction::LoxFunction, MapExpression::MapExpression, MapMethod::{map_key, MapMethod},
+    Module::{load_module, ModuleRegistry},
+    NativeFunction::{Arity, HostFunction, NativeFunction, NATIVES},
     PrintStatement::PrintStatement, ReturnStatement::ReturnStatement,
-    SuperExpression::SuperExpression, Token::Token, UnaryExpression::UnaryExpression,
+    SuperExpression::SuperExpression, ThrowStatement::ThrowStatement, Token::Token,
+    TryStatement::TryStatement, UnaryExpression::UnaryExpression,
     VariableExpression::VariableExpression, VariableStatement::VariableStatement,
     WhileStatement::WhileStatement,
 };
@@ -40,45 +52,224 @@ pub type SharedEnv = Rc<RefCell<Environment>>;
 pub struct Interpreter {
     pub environment: SharedEnv,
     pub locals: HashMap<u32, usize>,
+    /// When set, `print` appends here instead of writing to stdout.
+    pub output: Option<String>,
+    /// The value of the `throw` currently unwinding the stack. The error it
+    /// travels with only carries a message, so a `catch` picks the value up
+    /// from here.
+    pub thrown: Option<LiteralValue>,
+    pub modules: ModuleRegistry,
+    /// How many Lox function calls are running, checked against
+    /// `MAX_CALL_DEPTH`.
+    pub call_depth: usize,
+    /// The built-in `Error` class of the objects runtime errors become,
+    /// created by the first one caught.
+    error_class: Option<LoxClass>,
 }
 
-impl InterpreterTrait for Interpreter {
-    fn define_globals(&mut self) {
+/// The value a `catch` clause receives for a runtime error raised by the
+/// interpreter itself: an instance of `klass`, the built-in `Error` class,
+/// with `message` and `line` fields.
+fn error_object(klass: LoxClass, error: &LoxError) -> LiteralValue {
+    let fields = HashMap::from([
+        (
+            String::from("message"),
+            LiteralValue::String(error.message().to_string()),
+        ),
+        (
+            String::from("line"),
+            LiteralValue::Number(error.line() as f64),
+        ),
+    ]);
+    LiteralValue::Instance(Rc::new(RefCell::new(LoxInstance { klass, fields })))
+}
+
+/// The message an uncaught `throw` reports. Rethrowing a caught error object
+/// keeps its original message; instances of a user class that happens to be
+/// called `Error` are reported like any other value.
+fn uncaught_message(value: &LiteralValue, error_class: Option<&LoxClass>) -> String {
+    if let (LiteralValue::Instance(instance), Some(error_class)) = (value, error_class) {
+        let instance = instance.borrow();
+        if let Some(LiteralValue::String(message)) = instance.fields.get("message") {
+            if Rc::ptr_eq(&instance.klass.fields, &error_class.fields) {
+                return message.clone();
+            }
+        }
+    }
+    match value {
+        LiteralValue::Number(n) => format!("Uncaught exception: {}", n),
+        _ => format!("Uncaught exception: {}", value),
+    }
+}
+
+impl Interpreter {
+    /// The class of every error object, shared so that catching two errors
+    /// gives instances of the same class.
+    fn error_class(&mut self) -> LoxClass {
+        self.error_class
+            .get_or_insert_with(|| LoxClass {
+                name: String::from("Error"),
+                methods: HashMap::new(),
+                static_methods: HashMap::new(),
+                setters: HashMap::new(),
+                fields: Rc::new(RefCell::new(HashMap::new())),
+                superclass: None,
+            })
+            .clone()
+    }
+
+    /// Defines `native` as a global.
+    pub fn register_native(&mut self, native: NativeFunction) {
         self.environment
             .borrow_mut()
-            .define(String::from("clock"), LiteralValue::Clock(Clock {}));
+            .define(native.name.clone(), LiteralValue::Native(native));
+    }
+
+    /// Exposes a host closure that receives the raw argument list. It
+    /// accepts any number of arguments.
+    pub fn register_fn<F, R>(&mut self, name: &str, function: F)
+    where
+        F: Fn(Vec<LiteralValue>) -> R + 'static,
+        R: IntoNativeResult,
+    {
+        self.register_native(NativeFunction::new(
+            name,
+            Arity::VARIADIC(0),
+            move |_, arguments| function(arguments).into_native_result(),
+        ));
+    }
+
+    /// Exposes a host closure with typed parameters. The arity comes from the
+    /// closure, and arguments of the wrong type raise a runtime error naming
+    /// the expected type.
+    pub fn register_typed<Args, F>(&mut self, name: &str, function: F)
+    where
+        F: HostFunction<Args> + 'static,
+    {
+        let function_name = name.to_string();
+        self.register_native(NativeFunction::new(
+            name,
+            Arity::FIXED(F::arity()),
+            move |_, arguments| function.call_with(&function_name, arguments),
+        ));
+    }
+
+    /// Runs `left <operator> right` through the operator method of `left`'s
+    /// class, such as `__add__` for `+`. Returns `None` when `left` is not an
+    /// instance, or for `==` and `!=` on a class without `__eq__`, which keep
+    /// the built-in comparison.
+    fn call_operator(
+        &mut self,
+        left: &LiteralValue,
+        right: &LiteralValue,
+        operator: &Token,
+    ) -> Result<Option<LiteralValue>, LoxError> {
+        let (LiteralValue::Instance(instance), Some(method_name)) =
+            (left, operator_method(&operator.token_value))
+        else {
+            return Ok(None);
+        };
+        let method = instance.borrow().klass.find_method(method_name.to_string());
+        let Some(method) = method else {
+            // Without `__add__`, an instance with `toString()` still
+            // concatenates with a string.
+            let concatenates = method_name == "__add__"
+                && matches!(right, LiteralValue::String(_))
+                && Interpreter::to_string_method(left).is_some();
+            if method_name == "__eq__" || concatenates {
+                return Ok(None);
+            }
+            return Err(self.error(
+                format!(
+                    "Operator '{}' is not defined for {}; add an '{}' method.",
+                    operator.token_value,
+                    instance.borrow().klass.name,
+                    method_name
+                ),
+                operator,
+            ));
+        };
+        let result = method
+            .bind(left.clone())
+            .call(self, vec![right.clone()])
+            .map_err(|err| err.with_frame(method_name, operator.line))?;
+        if operator.token_type == TokenType::BANG_EQUAL {
+            return Ok(Some(LiteralValue::Boolean(!self.is_truthy(&result))));
+        }
+        Ok(Some(result))
+    }
+
+    /// The `toString` method of `value`'s class, if `value` is an instance
+    /// and the class or a superclass defines one.
+    fn to_string_method(value: &LiteralValue) -> Option<LoxFunction> {
+        match value {
+            LiteralValue::Instance(instance) => instance
+                .borrow()
+                .klass
+                .find_method(String::from("toString")),
+            _ => None,
+        }
+    }
+
+    /// The text `print` shows for `value`. Instances whose class defines
+    /// `toString()` are converted by calling it, including ones inside lists
+    /// and maps; whatever it returns is shown as a string.
+    pub fn stringify(&mut self, value: &LiteralValue) -> Result<String, LoxError> {
+        self.stringify_from(value, None)
+    }
+
+    /// [`Interpreter::stringify`] for a statement or expression on `line`,
+    /// which errors inside `toString()` name in their traceback.
+    fn stringify_from(&mut self, value: &LiteralValue, line: Option<u32>) -> Result<String, LoxError> {
+        let value = match Interpreter::to_string_method(value) {
+            Some(method) => {
+                let result = method.bind(value.clone()).call(self, Vec::new());
+                match line {
+                    Some(line) => result.map_err(|err| err.with_frame("toString", line))?,
+                    None => result?,
+                }
+            }
+            None => value.clone(),
+        };
+        match value {
+            LiteralValue::Number(n) => Ok(n.to_string()),
+            value => value.fmt_collection(&mut |instance| self.stringify_from(instance, line)),
+        }
+    }
+
+    /// Property reads return `value` as is, unless it is a bound getter, in
+    /// which case the getter runs and its result is the property's value.
+    fn run_getter(&mut self, value: LiteralValue, name: &Token) -> Result<LiteralValue, LoxError> {
+        match value {
+            LiteralValue::Function(mut getter) if getter.declaration.is_getter => getter
+                .call(self, Vec::new())
+                .map_err(|err| err.with_frame(&name.token_value, name.line)),
+            value => Ok(value),
+        }
+    }
+}
+
+impl InterpreterTrait for Interpreter {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn define_globals(&mut self) {
+        for (name, arity, function) in NATIVES {
+            self.register_native(NativeFunction::new(name, *arity, *function));
+        }
     }
 
     fn resolve(&mut self, expression: &mut dyn Expression, depth: usize) {
         self.locals.insert(expression.id(), depth);
     }
 
-    fn error(&self, message: String, token: &Token) -> String {
-        writeln!(io::stderr(), "{}", message).unwrap();
-        writeln!(io::stderr(), "[line {}]", token.line).unwrap();
-        exit(70);
+    fn error(&self, message: String, token: &Token) -> LoxError {
+        LoxError::runtime(token, message)
     }
 
     fn is_equal(&self, left: &LiteralValue, right: &LiteralValue) -> bool {
-        match left {
-            LiteralValue::Boolean(left_val) => match right {
-                LiteralValue::Boolean(right_val) => return left_val == right_val,
-                _ => return false,
-            },
-            LiteralValue::Nil => match right {
-                LiteralValue::Nil => return true,
-                _ => return false,
-            },
-            LiteralValue::Number(left_number) => match right {
-                LiteralValue::Number(right_number) => return left_number == right_number,
-                _ => return false,
-            },
-            LiteralValue::String(left_str) => match right {
-                LiteralValue::String(right_str) => return left_str == right_str,
-                _ => return false,
-            },
-            _ => false,
-        }
+        left == right
     }
 
     fn is_truthy(&self, object: &LiteralValue) -> bool {
@@ -89,21 +280,21 @@ impl InterpreterTrait for Interpreter {
         }
     }
 
-    fn evaluate(&mut self, expression: &mut Box<dyn Expression>) -> Result<LiteralValue, String> {
+    fn evaluate(&mut self, expression: &mut Box<dyn Expression>) -> Result<LiteralValue, LoxError> {
         return expression.interpret(self);
     }
 
     fn execute(
         &mut self,
         statement: &mut Box<dyn Statement>,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return statement.interpret(self);
     }
 
     fn visit_class_statement(
         &mut self,
         statement: &mut super::ClassStatement::ClassStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         let mut superclass = None;
         if let Some(statement_superclass) = &mut statement.super_class {
             let cloned = statement_superclass.clone();
@@ -111,10 +302,10 @@ impl InterpreterTrait for Interpreter {
             match self.evaluate(&mut boxed_cloned)? {
                 LiteralValue::LoxClass(loxcl) => superclass = Some(loxcl),
                 _ => {
-                    self.error(
+                    return Err(self.error(
                         String::from("Superclass must be a class."),
                         &statement_superclass.variable,
-                    );
+                    ));
                 }
             }
         }
@@ -155,6 +346,32 @@ impl InterpreterTrait for Interpreter {
                 unreachable!("ClassStatement.methods must all be functions");
             }
         }
+        let mut static_methods: HashMap<String, LoxFunction> = HashMap::new();
+        for method in &mut statement.static_methods {
+            if let Some(method_fn) = method.as_any_mut().downcast_mut::<FunctionStatement>() {
+                let fnc = LoxFunction {
+                    declaration: method_fn.clone(),
+                    closure: self.environment.clone(),
+                    is_initializer: false,
+                };
+                static_methods.insert(method_fn.name.token_value.clone(), fnc);
+            } else {
+                unreachable!("ClassStatement.static_methods must all be functions");
+            }
+        }
+        let mut setters: HashMap<String, LoxFunction> = HashMap::new();
+        for setter in &mut statement.setters {
+            if let Some(setter_fn) = setter.as_any_mut().downcast_mut::<FunctionStatement>() {
+                let fnc = LoxFunction {
+                    declaration: setter_fn.clone(),
+                    closure: self.environment.clone(),
+                    is_initializer: false,
+                };
+                setters.insert(setter_fn.name.token_value.clone(), fnc);
+            } else {
+                unreachable!("ClassStatement.setters must all be functions");
+            }
+        }
         let mut superclass_variable = None;
         if let Some(_) = &statement.super_class {
             superclass_variable = Some(Box::new(superclass.unwrap()))
@@ -162,6 +379,9 @@ impl InterpreterTrait for Interpreter {
         let klass = LoxClass {
             name: statement.name.token_value.clone(),
             methods,
+            static_methods,
+            setters,
+            fields: Rc::new(RefCell::new(HashMap::new())),
             superclass: superclass_variable,
         };
 
@@ -171,12 +391,12 @@ impl InterpreterTrait for Interpreter {
         self.environment
             .borrow_mut()
             .assign(statement.name.clone(), LiteralValue::LoxClass(klass))?;
-        return Ok(None);
+        return Ok(ControlFlow::NORMAL);
     }
     fn visit_super_expression(
         &mut self,
         expression: &mut SuperExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let depth = *self
             .locals
             .get(&expression.id)
@@ -196,12 +416,23 @@ impl InterpreterTrait for Interpreter {
             )
         })?;
 
-        if let (LiteralValue::LoxClass(super_cls), LiteralValue::Instance(ins_rc)) =
-            (super_val, this_val)
-        {
-            if let Some(method_fn) = super_cls.find_method(expression.method.token_value.clone()) {
-                return Ok(LiteralValue::Function(method_fn.bind(ins_rc)));
+        let method = expression.method.token_value.clone();
+        match (super_val, this_val) {
+            (LiteralValue::LoxClass(super_cls), this @ LiteralValue::Instance(_)) => {
+                if let Some(method_fn) = super_cls.find_method(method) {
+                    let bound = LiteralValue::Function(method_fn.bind(this));
+                    return self.run_getter(bound, &expression.method);
+                }
             }
+            // Inside a static method `this` is the class, so `super` reaches
+            // the superclass's static methods.
+            (LiteralValue::LoxClass(super_cls), this @ LiteralValue::LoxClass(_)) => {
+                if let Some(method_fn) = super_cls.find_static_method(&method) {
+                    let bound = LiteralValue::Function(method_fn.bind(this));
+                    return self.run_getter(bound, &expression.method);
+                }
+            }
+            _ => {}
         }
 
         Err(self.error(
@@ -213,144 +444,129 @@ impl InterpreterTrait for Interpreter {
     fn visit_binary_expression(
         &mut self,
         expression: &mut BinaryExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let left_val = self.evaluate(&mut expression.left)?;
         let right_val = self.evaluate(&mut expression.right)?;
-        let left_str = left_val.to_string();
-        let right_str = right_val.to_string();
-
-        let parse_f64 = |s: &str| s.parse::<f64>().map_err(|e| e.to_string());
-
+        if let Some(value) = self.call_operator(&left_val, &right_val, &expression.operator)? {
+            return Ok(value);
+        }
         match expression.operator.token_type {
-            TokenType::MINUS => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
-                    Ok(LiteralValue::Number((left_num - right_num).to_string()))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
+            TokenType::MINUS => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
+                    Ok(LiteralValue::Number(left_num - right_num))
                 }
-            }
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
             TokenType::PLUS => match (left_val, right_val) {
                 (LiteralValue::String(left_str), LiteralValue::String(right_str)) => {
                     Ok(LiteralValue::String(format!("{}{}", left_str, right_str)))
                 }
+                (LiteralValue::String(left_str), right)
+                    if Interpreter::to_string_method(&right).is_some() =>
+                {
+                    let right_str = self.stringify_from(&right, Some(expression.operator.line))?;
+                    Ok(LiteralValue::String(left_str + &right_str))
+                }
+                (left, LiteralValue::String(right_str))
+                    if Interpreter::to_string_method(&left).is_some() =>
+                {
+                    let left_str = self.stringify_from(&left, Some(expression.operator.line))?;
+                    Ok(LiteralValue::String(left_str + &right_str))
+                }
                 (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
-                    let left_f = parse_f64(&left_num).map_err(|_| {
-                        self.error(
-                            String::from("Operands must be numbers"),
-                            &expression.operator,
-                        )
-                    })?;
-                    let right_f = parse_f64(&right_num).map_err(|_| {
-                        self.error(
-                            String::from("Operands must be numbers"),
-                            &expression.operator,
-                        )
-                    })?;
-                    Ok(LiteralValue::Number((left_f + right_f).to_string()))
+                    Ok(LiteralValue::Number(left_num + right_num))
                 }
                 _ => Err(self.error(
                     "Operands must be two numbers or two strings".to_string(),
                     &expression.operator,
                 )),
             },
-
-            TokenType::STAR => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
-                    Ok(LiteralValue::Number((left_num * right_num).to_string()))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
+            TokenType::STAR => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
+                    Ok(LiteralValue::Number(left_num * right_num))
                 }
-            }
-            TokenType::SLASH => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
-                    Ok(LiteralValue::Number((left_num / right_num).to_string()))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
+            TokenType::SLASH => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
+                    Ok(LiteralValue::Number(left_num / right_num))
                 }
-            }
-            TokenType::GREATER => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
+            TokenType::GREATER => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
                     Ok(LiteralValue::Boolean(left_num > right_num))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
                 }
-            }
-            TokenType::GREATER_EQUAL => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
+            TokenType::GREATER_EQUAL => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
                     Ok(LiteralValue::Boolean(left_num >= right_num))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
                 }
-            }
-            TokenType::LESS => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
+            TokenType::LESS => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
                     Ok(LiteralValue::Boolean(left_num < right_num))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
                 }
-            }
-            TokenType::LESS_EQUAL => {
-                if let (Ok(left_num), Ok(right_num)) = (parse_f64(&left_str), parse_f64(&right_str))
-                {
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
+            TokenType::LESS_EQUAL => match (left_val, right_val) {
+                (LiteralValue::Number(left_num), LiteralValue::Number(right_num)) => {
                     Ok(LiteralValue::Boolean(left_num <= right_num))
-                } else {
-                    Err(self.error(
-                        String::from("Operands must be numbers."),
-                        &expression.operator,
-                    ))
                 }
-            }
+                _ => Err(self.error(
+                    String::from("Operands must be numbers."),
+                    &expression.operator,
+                )),
+            },
             TokenType::EQUAL_EQUAL => {
                 Ok(LiteralValue::Boolean(self.is_equal(&left_val, &right_val)))
             }
             TokenType::BANG_EQUAL => {
                 Ok(LiteralValue::Boolean(!self.is_equal(&left_val, &right_val)))
             }
-            _ => Err("Expression operator not right".to_string()),
+            _ => Err(self.error(
+                String::from("Expression operator not right"),
+                &expression.operator,
+            )),
         }
     }
 
-    fn visit_grouping(&mut self, expression: &mut Grouping) -> Result<LiteralValue, String> {
+    fn visit_grouping(&mut self, expression: &mut Grouping) -> Result<LiteralValue, LoxError> {
         return self.evaluate(&mut expression.expression);
     }
-    fn visit_literal(&self, expression: &Literal) -> Result<LiteralValue, String> {
+    fn visit_literal(&self, expression: &Literal) -> Result<LiteralValue, LoxError> {
         return Ok(expression.value.clone());
     }
 
     fn visit_unary_expression(
         &mut self,
         expression: &mut UnaryExpression,
-    ) -> Result<LiteralValue, String> {
-        let right = self.evaluate(&mut expression.expression).unwrap();
+    ) -> Result<LiteralValue, LoxError> {
+        let right = self.evaluate(&mut expression.expression)?;
         match expression.operator.token_type {
             TokenType::MINUS => match right {
                 LiteralValue::Number(number) => {
-                    let right_number = number.parse::<f64>().unwrap();
-                    return Ok(LiteralValue::Number((-1.0 * right_number).to_string()));
+                    return Ok(LiteralValue::Number(-number));
                 }
                 _ => Err(self.error(
                     String::from("Operand must be a number."),
@@ -367,7 +583,7 @@ impl InterpreterTrait for Interpreter {
     fn visit_this_expression(
         &mut self,
         expression: &mut super::ThisExpression::ThisExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         self.look_up_variable(&expression.value, expression)
     }
 
@@ -375,7 +591,7 @@ impl InterpreterTrait for Interpreter {
         &mut self,
         token: &Token,
         expr: &dyn Expression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         // See if we resolved it to some local depth
         if let Some(&distance) = self.locals.get(&expr.id()) {
             self.environment
@@ -397,14 +613,14 @@ impl InterpreterTrait for Interpreter {
     fn visit_variable_expression(
         &mut self,
         expression: &VariableExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         self.look_up_variable(&expression.variable, expression)
     }
 
     fn visit_logical_expression(
         &mut self,
         expression: &mut super::LogicalExpression::LogicalExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let left = self.evaluate(&mut expression.left)?;
         if expression.operator.token_type == TokenType::OR {
             if self.is_truthy(&left) {
@@ -421,7 +637,7 @@ impl InterpreterTrait for Interpreter {
     fn visit_assignment_expression(
         &mut self,
         expression: &mut AssignmentExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let value = self.evaluate(&mut expression.value)?;
 
         if let Some(&distance) = self.locals.get(&expression.id) {
@@ -439,12 +655,12 @@ impl InterpreterTrait for Interpreter {
     fn visit_get_expression(
         &mut self,
         expression: &mut GetExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let object = self.evaluate(&mut expression.expression)?;
         match object {
             LiteralValue::Instance(li) => match li.get(expression.name.clone()) {
                 Some(v) => {
-                    return Ok(v);
+                    return self.run_getter(v, &expression.name);
                 }
                 None => Err(self.error(
                     String::from(format!(
@@ -454,6 +670,39 @@ impl InterpreterTrait for Interpreter {
                     &expression.name,
                 )),
             },
+            LiteralValue::LoxClass(class) => match class.get(&expression.name.token_value) {
+                Some(value) => self.run_getter(value, &expression.name),
+                None => Err(self.error(
+                    format!("Undefined property '{}'.", expression.name.token_value),
+                    &expression.name,
+                )),
+            },
+            LiteralValue::List(list) => match ListMethod::bind(&list, &expression.name) {
+                Some(method) => Ok(LiteralValue::ListMethod(method)),
+                None => Err(self.error(
+                    format!("Undefined property '{}'.", expression.name.token_value),
+                    &expression.name,
+                )),
+            },
+            LiteralValue::Map(map) => match MapMethod::bind(&map, &expression.name) {
+                Some(method) => Ok(LiteralValue::MapMethod(method)),
+                None => Err(self.error(
+                    format!("Undefined property '{}'.", expression.name.token_value),
+                    &expression.name,
+                )),
+            },
+            LiteralValue::Module(module) => {
+                let member = module.globals.borrow().values.get(&expression.name.token_value).cloned();
+                member.ok_or_else(|| {
+                    self.error(
+                        format!(
+                            "Module '{}' has no member '{}'.",
+                            module.name, expression.name.token_value
+                        ),
+                        &expression.name,
+                    )
+                })
+            }
             _ => Err(self.error(
                 String::from("Only instances have properties."),
                 &expression.name,
@@ -464,12 +713,30 @@ impl InterpreterTrait for Interpreter {
     fn visit_set_expression(
         &mut self,
         expression: &mut super::SetExpression::SetExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let object = self.evaluate(&mut expression.expression)?;
         match object {
             LiteralValue::Instance(li) => {
                 let value = self.evaluate(&mut expression.value)?;
-                li.set(expression.name.clone(), value.clone());
+                let name = &expression.name;
+                let setter = li.borrow().klass.find_setter(&name.token_value);
+                match setter {
+                    Some(setter) => {
+                        setter
+                            .bind(LiteralValue::Instance(Rc::clone(&li)))
+                            .call(self, vec![value.clone()])
+                            .map_err(|err| err.with_frame(&name.token_value, name.line))?;
+                    }
+                    None => li.set(name.clone(), value.clone()),
+                }
+                return Ok(value);
+            }
+            LiteralValue::LoxClass(class) => {
+                let value = self.evaluate(&mut expression.value)?;
+                class
+                    .fields
+                    .borrow_mut()
+                    .insert(expression.name.token_value.clone(), value.clone());
                 return Ok(value);
             }
             _ => Err(self.error(
@@ -479,10 +746,102 @@ impl InterpreterTrait for Interpreter {
         }
     }
 
+    fn visit_function_expression(
+        &mut self,
+        expression: &mut FunctionExpression,
+    ) -> Result<LiteralValue, LoxError> {
+        Ok(LiteralValue::Function(LoxFunction {
+            declaration: expression.declaration.clone(),
+            closure: Rc::clone(&self.environment),
+            is_initializer: false,
+        }))
+    }
+
+    fn visit_list_expression(
+        &mut self,
+        expression: &mut ListExpression,
+    ) -> Result<LiteralValue, LoxError> {
+        let mut elements = Vec::new();
+        for element in &mut expression.elements {
+            elements.push(self.evaluate(element)?);
+        }
+        Ok(LiteralValue::List(Rc::new(RefCell::new(elements))))
+    }
+
+    fn visit_map_expression(
+        &mut self,
+        expression: &mut MapExpression,
+    ) -> Result<LiteralValue, LoxError> {
+        let mut entries = IndexMap::new();
+        for (key, value) in &mut expression.entries {
+            let key = self.evaluate(key)?;
+            let key = map_key(&key).map_err(|message| self.error(message, &expression.brace))?;
+            let value = self.evaluate(value)?;
+            entries.insert(key, value);
+        }
+        Ok(LiteralValue::Map(Rc::new(RefCell::new(entries))))
+    }
+
+    fn visit_index_expression(
+        &mut self,
+        expression: &mut IndexExpression,
+    ) -> Result<LiteralValue, LoxError> {
+        let object = self.evaluate(&mut expression.expression)?;
+        let index = self.evaluate(&mut expression.index)?;
+        match object {
+            LiteralValue::List(list) => {
+                let list = list.borrow();
+                let position = list_index(&index, list.len(), false)
+                    .map_err(|message| self.error(message, &expression.bracket))?;
+                Ok(list[position].clone())
+            }
+            LiteralValue::Map(map) => {
+                let key = map_key(&index)
+                    .map_err(|message| self.error(message, &expression.bracket))?;
+                match map.borrow().get(&key) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(self.error(format!("Undefined key {}.", key), &expression.bracket)),
+                }
+            }
+            _ => Err(self.error(
+                String::from("Only lists and maps can be indexed."),
+                &expression.bracket,
+            )),
+        }
+    }
+
+    fn visit_index_set_expression(
+        &mut self,
+        expression: &mut IndexSetExpression,
+    ) -> Result<LiteralValue, LoxError> {
+        let object = self.evaluate(&mut expression.expression)?;
+        let index = self.evaluate(&mut expression.index)?;
+        let value = self.evaluate(&mut expression.value)?;
+        match object {
+            LiteralValue::List(list) => {
+                let mut list = list.borrow_mut();
+                let position = list_index(&index, list.len(), false)
+                    .map_err(|message| self.error(message, &expression.bracket))?;
+                list[position] = value.clone();
+                Ok(value)
+            }
+            LiteralValue::Map(map) => {
+                let key = map_key(&index)
+                    .map_err(|message| self.error(message, &expression.bracket))?;
+                map.borrow_mut().insert(key, value.clone());
+                Ok(value)
+            }
+            _ => Err(self.error(
+                String::from("Only lists and maps can be indexed."),
+                &expression.bracket,
+            )),
+        }
+    }
+
     fn visit_call_expression(
         &mut self,
         expression: &mut CallExpression,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         let callee = self.evaluate(&mut expression.callee)?;
 
         let mut arguments = Vec::new();
@@ -503,8 +862,15 @@ impl InterpreterTrait for Interpreter {
                         &expression.paren,
                     ));
                 }
-                let res = fnc.call(self, arguments);
-                return Ok(res);
+                // Checked here as well as in `LoxFunction::call` so the
+                // error points at the call, as the VM's does.
+                if self.call_depth == MAX_CALL_DEPTH {
+                    return Err(self.error(String::from("Stack overflow."), &expression.paren));
+                }
+                let name = fnc.declaration.name.token_value.clone();
+                return fnc
+                    .call(self, arguments)
+                    .map_err(|err| err.with_frame(&name, expression.paren.line));
             }
             LiteralValue::LoxClass(mut cl) => {
                 if arguments.len() != cl.arity() {
@@ -517,22 +883,49 @@ impl InterpreterTrait for Interpreter {
                         &expression.paren,
                     ));
                 }
-                let res = cl.call(self, arguments);
-                return Ok(res);
+                return cl
+                    .call(self, arguments)
+                    .map_err(|err| err.with_frame("init", expression.paren.line));
             }
-            LiteralValue::Clock(mut fnc) => {
-                if arguments.len() != fnc.arity() {
+            LiteralValue::ListMethod(mut method) => {
+                if arguments.len() != method.arity() {
                     return Err(self.error(
                         format!(
                             "Expected {} arguments but got {}.",
-                            fnc.arity(),
+                            method.arity(),
                             arguments.len()
                         ),
                         &expression.paren,
                     ));
                 }
-
-                return Ok(fnc.call(self, arguments));
+                return method.call(self, arguments);
+            }
+            LiteralValue::MapMethod(mut method) => {
+                if arguments.len() != method.arity() {
+                    return Err(self.error(
+                        format!(
+                            "Expected {} arguments but got {}.",
+                            method.arity(),
+                            arguments.len()
+                        ),
+                        &expression.paren,
+                    ));
+                }
+                return method.call(self, arguments);
+            }
+            LiteralValue::Native(native) => {
+                if !native.arity.accepts(arguments.len()) {
+                    return Err(self.error(
+                        format!(
+                            "Expected {} arguments but got {}.",
+                            native.arity,
+                            arguments.len()
+                        ),
+                        &expression.paren,
+                    ));
+                }
+                return (native.function)(self, arguments)
+                    .map_err(|message| self.error(message, &expression.paren));
             }
             _ => {
                 return Err(self.error(
@@ -546,53 +939,59 @@ impl InterpreterTrait for Interpreter {
     fn visit_expression_statement(
         &mut self,
         statement: &mut ExpressionStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         self.evaluate(&mut statement.expression)?;
-        return Ok(None);
+        return Ok(ControlFlow::NORMAL);
     }
     fn visit_variable_statement(
         &mut self,
         statement: &mut VariableStatement,
-    ) -> Result<Option<LiteralValue>, String> {
-        let value = &self.evaluate(&mut statement.initializer);
+    ) -> Result<ControlFlow, LoxError> {
+        let value = self.evaluate(&mut statement.initializer)?;
         self.environment
             .borrow_mut()
-            .define(statement.name.token_value.clone(), value.clone().unwrap());
-        return Ok(None);
+            .define(statement.name.token_value.clone(), value);
+        return Ok(ControlFlow::NORMAL);
     }
     fn visit_print_statement(
         &mut self,
         statement: &mut PrintStatement,
-    ) -> Result<Option<LiteralValue>, String> {
-        let res = self.evaluate(&mut statement.expression).unwrap();
-        match res {
-            LiteralValue::Number(n) => {
-                println!("{}", n.parse::<f64>().unwrap());
+    ) -> Result<ControlFlow, LoxError> {
+        let res = self.evaluate(&mut statement.expression)?;
+        let line = self.stringify_from(&res, Some(statement.keyword.line))?;
+        match &mut self.output {
+            Some(buffer) => {
+                buffer.push_str(&line);
+                buffer.push('\n');
             }
-            _ => println!("{}", res.to_string()),
+            None => println!("{}", line),
         }
-        return Ok(None);
+        return Ok(ControlFlow::NORMAL);
     }
 
     fn visit_while_statement(
         &mut self,
         statement: &mut WhileStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         let mut condition_evaluation = self.evaluate(&mut statement.condition)?;
         while self.is_truthy(&condition_evaluation) {
             match self.execute(&mut statement.body)? {
-                Some(v) => return Ok(Some(v)),
-                None => {}
+                ControlFlow::BREAK => break,
+                ControlFlow::RETURN(value) => return Ok(ControlFlow::RETURN(value)),
+                ControlFlow::NORMAL | ControlFlow::CONTINUE => {}
             };
+            if let Some(increment) = &mut statement.increment {
+                self.evaluate(increment)?;
+            }
             condition_evaluation = self.evaluate(&mut statement.condition)?;
         }
-        return Ok(None);
+        return Ok(ControlFlow::NORMAL);
     }
 
     fn visit_function_statement(
         &mut self,
         statement: &mut FunctionStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         let name = statement.name.token_value.clone();
         let fnc = LoxFunction {
             declaration: statement.clone(),
@@ -602,26 +1001,104 @@ impl InterpreterTrait for Interpreter {
         self.environment
             .borrow_mut()
             .define(name, LiteralValue::Function(fnc));
-        return Ok(None);
+        return Ok(ControlFlow::NORMAL);
     }
 
     fn visit_return_statement(
         &mut self,
         statement: &mut ReturnStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         match &mut statement.value {
             Some(v) => {
                 let value = self.evaluate(v)?;
-                return Ok(Some(value));
+                return Ok(ControlFlow::RETURN(value));
             }
-            None => Ok(Some(LiteralValue::Nil)),
+            None => Ok(ControlFlow::RETURN(LiteralValue::Nil)),
         }
     }
 
+    fn visit_import_statement(
+        &mut self,
+        statement: &mut ImportStatement,
+    ) -> Result<ControlFlow, LoxError> {
+        let module = load_module(
+            self,
+            &statement.keyword,
+            &statement.path.token_value,
+            &statement.name.token_value,
+        )?;
+        self.environment.borrow_mut().define(
+            statement.name.token_value.clone(),
+            LiteralValue::Module(module),
+        );
+        Ok(ControlFlow::NORMAL)
+    }
+
+    fn visit_throw_statement(
+        &mut self,
+        statement: &mut ThrowStatement,
+    ) -> Result<ControlFlow, LoxError> {
+        let value = self.evaluate(&mut statement.value)?;
+        let message = uncaught_message(&value, self.error_class.as_ref());
+        let error = self.error(message, &statement.keyword);
+        self.thrown = Some(value);
+        Err(error)
+    }
+
+    fn visit_try_statement(
+        &mut self,
+        statement: &mut TryStatement,
+    ) -> Result<ControlFlow, LoxError> {
+        let mut result = self.visit_block_statement(&mut statement.body);
+
+        if let Some((name, handler)) = &mut statement.catch_clause {
+            if let Err(error @ LoxError::Runtime { .. }) = &result {
+                let value = match self.thrown.take() {
+                    Some(value) => value,
+                    None => error_object(self.error_class(), error),
+                };
+                let parent = Rc::clone(&self.environment);
+                let mut scope = Environment {
+                    values: HashMap::new(),
+                    enclosing: Some(parent),
+                };
+                scope.define(name.token_value.clone(), value);
+                let old = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(scope)));
+                result = self.execute_block(&mut handler.statements);
+                self.environment = old;
+            }
+        }
+
+        if let Some(finally_block) = &mut statement.finally_block {
+            // A throw still in flight resumes once the finally block is done,
+            // unless the block leaves some other way.
+            let pending = self.thrown.take();
+            match self.visit_block_statement(finally_block)? {
+                ControlFlow::NORMAL => self.thrown = pending,
+                flow => return Ok(flow),
+            }
+        }
+        result
+    }
+
+    fn visit_break_statement(
+        &mut self,
+        _statement: &mut BreakStatement,
+    ) -> Result<ControlFlow, LoxError> {
+        Ok(ControlFlow::BREAK)
+    }
+
+    fn visit_continue_statement(
+        &mut self,
+        _statement: &mut ContinueStatement,
+    ) -> Result<ControlFlow, LoxError> {
+        Ok(ControlFlow::CONTINUE)
+    }
+
     fn visit_block_statement(
         &mut self,
         statement: &mut BlockStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         // 1) build a brand‑new frame whose parent is the old one
         let parent = Rc::clone(&self.environment);
         let child = Rc::new(RefCell::new(Environment {
@@ -633,64 +1110,52 @@ impl InterpreterTrait for Interpreter {
         let old = std::mem::replace(&mut self.environment, child);
 
         // 3) run statements
-        let mut result = None;
-        for stmt in &mut statement.statements {
-            if let Some(v) = self.execute(stmt)? {
-                result = Some(v);
-                break;
-            }
-        }
+        let result = self.execute_block(&mut statement.statements);
 
-        // 4) restore the old pointer
+        // 4) restore the old pointer, even when a statement failed
         self.environment = old;
-        Ok(result)
+        result
     }
 
     fn execute_block(
         &mut self,
         statements: &mut Vec<Box<dyn Statement>>,
-    ) -> Result<Option<LiteralValue>, String> {
-        let mut result = None;
+    ) -> Result<ControlFlow, LoxError> {
         for stmt in statements {
-            let res = self.execute(stmt)?;
-            if res.is_some() {
-                result = res;
-                break;
+            match self.execute(stmt)? {
+                ControlFlow::NORMAL => {}
+                flow => return Ok(flow),
             }
         }
-        Ok(result)
+        Ok(ControlFlow::NORMAL)
     }
 
     fn visit_if_statement(
         &mut self,
         statement: &mut IfStatement,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         let condition = self.evaluate(&mut statement.condition)?;
         if self.is_truthy(&condition) {
-            // *Propagate* whatever the then‐branch returns (Some or None)
+            // *Propagate* whatever the then‐branch signals (return, break, ...)
             return self.execute(&mut statement.then_statement);
         } else if let Some(else_branch) = &mut statement.else_statement {
             // Likewise for an else branch
             return self.execute(else_branch);
         }
         // No return executed, fall through
-        Ok(None)
+        Ok(ControlFlow::NORMAL)
     }
 
     fn interpret(
         &mut self,
         statements: &mut Vec<Box<dyn Statement>>,
-    ) -> Result<Option<LiteralValue>, String> {
-        let mut ret_val = None;
+    ) -> Result<ControlFlow, LoxError> {
         for statement in statements {
             match self.execute(statement)? {
-                Some(rv) => {
-                    ret_val = Some(rv);
-                    break;
-                }
-                None => {}
+                ControlFlow::NORMAL => {}
+                flow => return Ok(flow),
             }
         }
-        return Ok(ret_val);
+        return Ok(ControlFlow::NORMAL);
     }
 }
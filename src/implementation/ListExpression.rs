@@ -0,0 +1,55 @@
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{AstPrinter::AstPrinterTrait, Expression::Expression},
+};
+
+use super::{AstPrinter::AstPrinter, Token::Token};
+
+/// A list literal such as `[1, 2, 3]`.
+pub struct ListExpression {
+    pub bracket: Token,
+    pub elements: Vec<Box<dyn Expression>>,
+    pub id: u32,
+}
+
+impl Clone for ListExpression {
+    fn clone(&self) -> Self {
+        ListExpression {
+            bracket: self.bracket.clone(),
+            elements: self
+                .elements
+                .iter()
+                .map(|element| element.clone_box())
+                .collect(),
+            id: self.id,
+        }
+    }
+}
+
+impl Expression for ListExpression {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn Expression> {
+        Box::new(self.clone())
+    }
+    fn expression_print(&self) -> String {
+        AstPrinter {}.paranthesize(String::from("list"), &self.elements.iter().collect())
+    }
+    fn id(&self) -> u32 {
+        self.id
+    }
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_list_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_list_expression(self);
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
+    ) -> Result<LiteralValue, LoxError> {
+        return interpreter.visit_list_expression(self);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, LoxCallableTrait::LoxCallableTrait},
+};
+
+use super::{Interpreter::Interpreter, Token::Token};
+
+pub type ListRef = Rc<RefCell<Vec<LiteralValue>>>;
+
+/// A native list method bound to its list, e.g. the value of `xs.push`.
+/// `name` is the property token, so errors point at the method name.
+#[derive(Clone)]
+pub struct ListMethod {
+    pub list: ListRef,
+    pub name: Token,
+}
+
+impl ListMethod {
+    /// Binds `name` to `list`, or returns `None` if lists have no such method.
+    pub fn bind(list: &ListRef, name: &Token) -> Option<ListMethod> {
+        match name.token_value.as_str() {
+            "push" | "pop" | "len" | "insert" | "remove" => Some(ListMethod {
+                list: Rc::clone(list),
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Converts `index` to a position in a list of `len` elements. `insert` may
+/// also use `len` itself to append.
+pub fn list_index(index: &LiteralValue, len: usize, allow_end: bool) -> Result<usize, String> {
+    let position = match index {
+        LiteralValue::Number(n) if n.fract() == 0.0 => *n,
+        _ => return Err(String::from("List index must be an integer.")),
+    };
+    let limit = if allow_end { len + 1 } else { len };
+    if position < 0.0 || position >= limit as f64 {
+        return Err(String::from("List index out of range."));
+    }
+    Ok(position as usize)
+}
+
+impl LoxCallableTrait for ListMethod {
+    fn arity(&mut self) -> usize {
+        match self.name.token_value.as_str() {
+            "push" | "remove" => 1,
+            "insert" => 2,
+            _ => 0,
+        }
+    }
+
+    fn call(
+        &mut self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LiteralValue>,
+    ) -> Result<LiteralValue, LoxError> {
+        let mut list = self.list.borrow_mut();
+        let mut arguments = arguments.into_iter();
+        match self.name.token_value.as_str() {
+            "push" => {
+                list.push(arguments.next().unwrap_or_default());
+                Ok(LiteralValue::Nil)
+            }
+            "pop" => list.pop().ok_or_else(|| {
+                interpreter.error(String::from("Can't pop from an empty list."), &self.name)
+            }),
+            "len" => Ok(LiteralValue::Number(list.len() as f64)),
+            "insert" => {
+                let index = arguments.next().unwrap_or_default();
+                let index = list_index(&index, list.len(), true)
+                    .map_err(|message| interpreter.error(message, &self.name))?;
+                list.insert(index, arguments.next().unwrap_or_default());
+                Ok(LiteralValue::Nil)
+            }
+            _ => {
+                let index = arguments.next().unwrap_or_default();
+                let index = list_index(&index, list.len(), false)
+                    .map_err(|message| interpreter.error(message, &self.name))?;
+                Ok(list.remove(index))
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::any::Any;
 use rand::{rng, Rng};
 
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AstPrinter::AstPrinter;
 use crate::traits::AstPrinter::AstPrinterTrait;
 use crate::traits::Expression::Expression;
@@ -51,14 +52,18 @@ impl Expression for Literal {
         self.id
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_literal_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_literal_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_literal_expression(self);
     }
 
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_literal(self);
     }
     fn as_any(&self) -> &dyn Any {
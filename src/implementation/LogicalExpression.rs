@@ -1,4 +1,5 @@
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::Token::Token;
 use crate::traits::Expression::Expression;
 use crate::traits::Interpreter::InterpreterTrait;
@@ -31,13 +32,17 @@ impl Expression for LogicalExpression {
         self.id
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_logical_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_logical_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_logical_expression(self);
     }
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_logical_expression(self);
     }
     fn as_any(&self) -> &dyn Any {
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, path::PathBuf, rc::Rc};
+
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, LoxConvert::IntoNativeResult, Statement::Statement},
+    utils::tokenizer::tokenize,
+};
+
+use super::{
+    Interpreter::{Interpreter, SharedEnv},
+    NativeFunction::HostFunction,
+    Parser::Parser,
+    Resolver::{ClassType, FunctionType, Resolver},
+};
+
+/// An embeddable interpreter session. Globals, functions and classes defined
+/// by one call stay visible to the next.
+pub struct Lox {
+    resolver: Resolver,
+    globals: SharedEnv,
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Lox::new()
+    }
+}
+
+impl Lox {
+    pub fn new() -> Self {
+        let mut interpreter = Interpreter::default();
+        interpreter.define_globals();
+        let globals = Rc::clone(&interpreter.environment);
+        Lox {
+            resolver: Resolver {
+                scopes: Vec::from([HashMap::new()]),
+                interpreter: Box::new(interpreter),
+                current_function: FunctionType::NONE,
+                current_class: ClassType::NONE,
+                loop_depth: 0,
+            },
+            globals,
+        }
+    }
+
+    fn interpreter(&mut self) -> &mut Interpreter {
+        self.resolver
+            .interpreter
+            .as_any_mut()
+            .downcast_mut::<Interpreter>()
+            .expect("a Lox session always runs the tree-walking Interpreter")
+    }
+
+    /// Buffers everything `print` writes instead of sending it to stdout.
+    pub fn capture_output(&mut self) {
+        self.interpreter().output.get_or_insert_with(String::new);
+    }
+
+    /// Returns and clears the output captured since the last call.
+    pub fn take_output(&mut self) -> String {
+        self.interpreter()
+            .output
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Runs the front end (tokenize, parse, resolve) over `source` without
+    /// executing it. Lex errors are reported together with the parse error.
+    pub fn parse_and_resolve(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<Box<dyn Statement>>, Vec<LoxError>> {
+        self.parse_and_resolve_in(source, 0)
+    }
+
+    fn parse_and_resolve_in(
+        &mut self,
+        source: &str,
+        file: u32,
+    ) -> Result<Vec<Box<dyn Statement>>, Vec<LoxError>> {
+        let result = tokenize(source.to_string()).in_file(file);
+        let mut errors = result.errors;
+        let mut parser = Parser::new(result.tokens);
+        let parsed = parser.parse();
+        let mut statements = match parsed {
+            Ok(statements) if errors.is_empty() => statements,
+            Ok(_) => return Err(errors),
+            Err(parse_errors) => {
+                errors.extend(parse_errors);
+                return Err(errors);
+            }
+        };
+        if let Err(error) = self.resolver.resolve_statements(&mut statements) {
+            self.recover();
+            return Err(vec![error]);
+        }
+        Ok(statements)
+    }
+
+    pub fn run_source(&mut self, source: &str) -> Result<(), Vec<LoxError>> {
+        self.run_source_in(source, 0)
+    }
+
+    fn run_source_in(&mut self, source: &str, file: u32) -> Result<(), Vec<LoxError>> {
+        let mut statements = self.parse_and_resolve_in(source, file)?;
+        if let Err(error) = self.resolver.interpreter.interpret(&mut statements) {
+            let mut errors = vec![error];
+            errors.extend(self.interpreter().modules.take_errors());
+            self.recover();
+            return Err(errors);
+        }
+        Ok(())
+    }
+
+    pub fn eval_expression(&mut self, source: &str) -> Result<LiteralValue, Vec<LoxError>> {
+        let result = tokenize(source.to_string());
+        if !result.errors.is_empty() {
+            return Err(result.errors);
+        }
+        let mut expression = Parser::new(result.tokens).parse_expression()?;
+        let result = expression
+            .resolve(&mut self.resolver)
+            .and_then(|_| expression.interpret(self.resolver.interpreter.as_mut()));
+        result.map_err(|error| {
+            self.recover();
+            vec![error]
+        })
+    }
+
+    /// Runs one chunk of interactive input. A bare expression (no trailing
+    /// `;`) is evaluated and its value returned so the caller can echo it;
+    /// anything else runs as ordinary statements. Each input is kept under
+    /// its own file id, so [`Lox::source_of`] finds the right text even for
+    /// errors raised later inside functions it defined.
+    pub fn run_interactive(&mut self, source: &str) -> Result<Option<LiteralValue>, Vec<LoxError>> {
+        let file = self
+            .interpreter()
+            .modules
+            .add_source(PathBuf::from("<repl>"), source.to_string());
+        let result = tokenize(source.to_string()).in_file(file);
+        if result.errors.is_empty() {
+            let mut parser = Parser::new(result.tokens);
+            if let Ok(mut expression) = parser.parse_expression() {
+                if parser.is_at_end().unwrap_or(false) {
+                    let result = expression
+                        .resolve(&mut self.resolver)
+                        .and_then(|_| expression.interpret(self.resolver.interpreter.as_mut()));
+                    return result.map(Some).map_err(|error| {
+                        self.recover();
+                        vec![error]
+                    });
+                }
+            }
+        }
+        self.run_source_in(source, file).map(|_| None)
+    }
+
+    /// The text `print` would show for `value`, running `toString()` where
+    /// its class defines one.
+    pub fn stringify(&mut self, value: &LiteralValue) -> Result<String, Vec<LoxError>> {
+        let result = self.interpreter().stringify(value);
+        result.map_err(|error| {
+            self.recover();
+            vec![error]
+        })
+    }
+
+    /// Puts the session back at global scope after an error unwound it
+    /// part-way through a block or function.
+    fn recover(&mut self) {
+        self.resolver.reset();
+        let globals = Rc::clone(&self.globals);
+        let interpreter = self.interpreter();
+        interpreter.environment = globals;
+        interpreter.thrown = None;
+        interpreter.modules.reset();
+    }
+
+    /// Sets the file the session's source comes from, so relative imports
+    /// resolve next to it rather than in the working directory.
+    pub fn set_script_path(&mut self, path: &str) {
+        self.interpreter().modules.script_path = Some(PathBuf::from(path));
+    }
+
+    /// The path and text of the imported module or REPL input `error` was
+    /// raised in, for rendering it against the right source. `None` means
+    /// the error belongs to the main script.
+    pub fn source_of(&mut self, error: &LoxError) -> Option<(String, String)> {
+        let (path, source) = self.interpreter().modules.source_of(error)?;
+        Some((path.display().to_string(), source.to_string()))
+    }
+
+    /// Exposes a host closure to scripts; see [`Interpreter::register_fn`].
+    pub fn register_fn<F, R>(&mut self, name: &str, function: F)
+    where
+        F: Fn(Vec<LiteralValue>) -> R + 'static,
+        R: IntoNativeResult,
+    {
+        self.interpreter().register_fn(name, function);
+    }
+
+    /// Exposes a typed host closure to scripts; see
+    /// [`Interpreter::register_typed`].
+    pub fn register_typed<Args, F>(&mut self, name: &str, function: F)
+    where
+        F: HostFunction<Args> + 'static,
+    {
+        self.interpreter().register_typed(name, function);
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<LiteralValue> {
+        self.globals.borrow().values.get(name).cloned()
+    }
+
+    pub fn set_global(&mut self, name: &str, value: LiteralValue) {
+        self.globals.borrow_mut().define(name.to_string(), value);
+    }
+}
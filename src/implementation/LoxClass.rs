@@ -1,16 +1,25 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{
-    enums::{LiteralValue::LiteralValue, TokenType::TokenType},
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError, TokenType::TokenType},
     traits::LoxCallableTrait::LoxCallableTrait,
 };
 
 use super::{LoxFunction::LoxFunction, LoxInstance::LoxInstance, Token::Token};
 
+/// A class value. Copies of it share `fields`, so a static field set through
+/// one copy is seen by all of them.
 #[derive(Clone)]
 pub struct LoxClass {
     pub name: String,
     pub methods: HashMap<String, LoxFunction>,
+    /// Methods declared `static`, called on the class with `this` bound to it.
+    pub static_methods: HashMap<String, LoxFunction>,
+    /// Methods declared `set name(value)`. Assigning `name` on an instance
+    /// calls the setter instead of writing the field.
+    pub setters: HashMap<String, LoxFunction>,
+    /// Static fields, set with `Class.name = value`.
+    pub fields: Rc<RefCell<HashMap<String, LiteralValue>>>,
     pub superclass: Option<Box<LoxClass>>,
 }
 
@@ -25,17 +34,17 @@ impl LoxCallableTrait for LoxClass {
         &mut self,
         interpreter: &mut super::Interpreter::Interpreter,
         arguments: Vec<crate::enums::LiteralValue::LiteralValue>,
-    ) -> LiteralValue {
+    ) -> Result<LiteralValue, LoxError> {
         let lox_instance = Rc::new(RefCell::new(LoxInstance {
             klass: self.clone(),
             fields: HashMap::new(),
         }));
         if let Some(i) = self.find_method(String::from("init")) {
-            let mut binded = i.bind(Rc::clone(&lox_instance));
-            binded.call(interpreter, arguments);
+            let mut binded = i.bind(LiteralValue::Instance(Rc::clone(&lox_instance)));
+            binded.call(interpreter, arguments)?;
         }
 
-        return LiteralValue::Instance(lox_instance);
+        return Ok(LiteralValue::Instance(lox_instance));
     }
 }
 
@@ -49,4 +58,37 @@ impl LoxClass {
         }
         return None;
     }
+
+    pub fn find_setter(&self, name: &str) -> Option<LoxFunction> {
+        if let Some(setter) = self.setters.get(name) {
+            return Some(setter.clone());
+        }
+        self.superclass.as_ref()?.find_setter(name)
+    }
+
+    pub fn find_static_method(&self, method_name: &str) -> Option<LoxFunction> {
+        if let Some(method) = self.static_methods.get(method_name) {
+            return Some(method.clone());
+        }
+        self.superclass.as_ref()?.find_static_method(method_name)
+    }
+
+    fn find_field(&self, name: &str) -> Option<LiteralValue> {
+        if let Some(value) = self.fields.borrow().get(name) {
+            return Some(value.clone());
+        }
+        self.superclass.as_ref()?.find_field(name)
+    }
+
+    /// Reads `Class.name`: a static field, or else a static method bound to
+    /// this class. Both are inherited from superclasses.
+    pub fn get(&self, name: &str) -> Option<LiteralValue> {
+        if let Some(value) = self.find_field(name) {
+            return Some(value);
+        }
+        let method = self.find_static_method(name)?;
+        Some(LiteralValue::Function(
+            method.bind(LiteralValue::LoxClass(self.clone())),
+        ))
+    }
 }
@@ -1,7 +1,8 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    constants::constants::MAX_CALL_DEPTH,
+    enums::{ControlFlow::ControlFlow, LiteralValue::LiteralValue, LoxError::LoxError},
     traits::{Interpreter::InterpreterTrait, LoxCallableTrait::LoxCallableTrait},
 };
 
@@ -10,7 +11,6 @@ use super::{
     Environment::{EnvExt, Environment},
     FunctionStatement::FunctionStatement,
     Interpreter::{Interpreter, SharedEnv},
-    LoxInstance::LoxInstance,
 };
 
 pub struct LoxFunction {
@@ -30,15 +30,14 @@ impl Clone for LoxFunction {
 }
 
 impl LoxFunction {
-    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+    /// Returns a copy of the method with `this` bound to `receiver`, an
+    /// instance for ordinary methods or the class itself for static ones.
+    pub fn bind(&self, receiver: LiteralValue) -> LoxFunction {
         // 1) Create a new child environment whose parent is our closure
         let env = {
             let mut map = HashMap::new();
             // Define "this" in that new scope
-            map.insert(
-                "this".to_string(),
-                LiteralValue::Instance(Rc::clone(&instance)),
-            );
+            map.insert("this".to_string(), receiver);
             Rc::new(RefCell::new(Environment {
                 values: map,
                 enclosing: Some(Rc::clone(&self.closure)),
@@ -63,7 +62,13 @@ impl LoxCallableTrait for LoxFunction {
         &mut self,
         interpreter: &mut Interpreter,
         arguments: Vec<LiteralValue>,
-    ) -> LiteralValue {
+    ) -> Result<LiteralValue, LoxError> {
+        if interpreter.call_depth == MAX_CALL_DEPTH {
+            return Err(LoxError::runtime(
+                &self.declaration.name,
+                String::from("Stack overflow."),
+            ));
+        }
         let parent = Rc::clone(&self.closure);
         let child = Rc::new(RefCell::new(Environment {
             values: HashMap::new(),
@@ -88,17 +93,22 @@ impl LoxCallableTrait for LoxFunction {
             .iter()
             .map(|s| s.clone_box())
             .collect::<Vec<_>>();
-        let result = interpreter
-            .execute_block(&mut body)
-            .expect("Function body must execute");
+        interpreter.call_depth += 1;
+        let result = interpreter.execute_block(&mut body);
+        interpreter.call_depth -= 1;
 
+        // 5) Restore the caller's frame, even when the body failed
         interpreter.environment = old_env;
+        let result = result?;
 
         if self.is_initializer {
-            return self.closure.get_at(0, "this").unwrap();
+            return Ok(self.closure.get_at(0, "this").unwrap());
         }
 
         // 6) Return the function’s return‐value or Nil
-        result.unwrap_or(LiteralValue::Nil)
+        match result {
+            ControlFlow::RETURN(value) => Ok(value),
+            _ => Ok(LiteralValue::Nil),
+        }
     }
 }
@@ -22,7 +22,7 @@ impl LoxInstanceExt for Rc<RefCell<LoxInstance>> {
         if let Some(val) = inst.fields.get(&token.token_value) {
             Some(val.clone())
         } else if let Some(mut method) = inst.klass.find_method(token.token_value.clone()) {
-            let bound = method.bind(Rc::clone(self));
+            let bound = method.bind(LiteralValue::Instance(Rc::clone(self)));
             Some(LiteralValue::Function(bound))
         } else {
             None
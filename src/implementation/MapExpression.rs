@@ -0,0 +1,60 @@
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{AstPrinter::AstPrinterTrait, Expression::Expression},
+};
+
+use super::{AstPrinter::AstPrinter, Token::Token};
+
+/// A map literal such as `{"a": 1, "b": 2}`.
+pub struct MapExpression {
+    pub brace: Token,
+    pub entries: Vec<(Box<dyn Expression>, Box<dyn Expression>)>,
+    pub id: u32,
+}
+
+impl Clone for MapExpression {
+    fn clone(&self) -> Self {
+        MapExpression {
+            brace: self.brace.clone(),
+            entries: self
+                .entries
+                .iter()
+                .map(|(key, value)| (key.clone_box(), value.clone_box()))
+                .collect(),
+            id: self.id,
+        }
+    }
+}
+
+impl Expression for MapExpression {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn Expression> {
+        Box::new(self.clone())
+    }
+    fn expression_print(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .flat_map(|(key, value)| [key, value])
+            .collect();
+        AstPrinter {}.paranthesize(String::from("map"), &entries)
+    }
+    fn id(&self) -> u32 {
+        self.id
+    }
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_map_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_map_expression(self);
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
+    ) -> Result<LiteralValue, LoxError> {
+        return interpreter.visit_map_expression(self);
+    }
+}
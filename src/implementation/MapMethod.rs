@@ -0,0 +1,74 @@
+use std::{cell::RefCell, rc::Rc};
+
+use indexmap::IndexMap;
+
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError, MapKey::MapKey},
+    traits::{Interpreter::InterpreterTrait, LoxCallableTrait::LoxCallableTrait},
+};
+
+use super::{Interpreter::Interpreter, Token::Token};
+
+/// Entries keep insertion order, so `keys()` and printing are predictable.
+pub type MapRef = Rc<RefCell<IndexMap<MapKey, LiteralValue>>>;
+
+/// A native map method bound to its map, e.g. the value of `m.keys`.
+/// `name` is the property token, so errors point at the method name.
+#[derive(Clone)]
+pub struct MapMethod {
+    pub map: MapRef,
+    pub name: Token,
+}
+
+impl MapMethod {
+    /// Binds `name` to `map`, or returns `None` if maps have no such method.
+    pub fn bind(map: &MapRef, name: &Token) -> Option<MapMethod> {
+        match name.token_value.as_str() {
+            "keys" | "values" | "has" | "remove" | "len" => Some(MapMethod {
+                map: Rc::clone(map),
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a Lox value to a map key, or explains why it can't be one.
+pub fn map_key(value: &LiteralValue) -> Result<MapKey, String> {
+    MapKey::from_literal(value).ok_or_else(|| {
+        String::from(
+            "Map keys must be strings, numbers, booleans, nil, instances, classes or functions.",
+        )
+    })
+}
+
+impl LoxCallableTrait for MapMethod {
+    fn arity(&mut self) -> usize {
+        match self.name.token_value.as_str() {
+            "has" | "remove" => 1,
+            _ => 0,
+        }
+    }
+
+    fn call(
+        &mut self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LiteralValue>,
+    ) -> Result<LiteralValue, LoxError> {
+        let mut map = self.map.borrow_mut();
+        let list = |values: Vec<LiteralValue>| LiteralValue::List(Rc::new(RefCell::new(values)));
+        match self.name.token_value.as_str() {
+            "keys" => Ok(list(map.keys().map(MapKey::to_literal).collect())),
+            "values" => Ok(list(map.values().cloned().collect())),
+            "len" => Ok(LiteralValue::Number(map.len() as f64)),
+            name => {
+                let key = map_key(&arguments[0])
+                    .map_err(|message| interpreter.error(message, &self.name))?;
+                if name == "has" {
+                    return Ok(LiteralValue::Boolean(map.contains_key(&key)));
+                }
+                Ok(map.shift_remove(&key).unwrap_or_default())
+            }
+        }
+    }
+}
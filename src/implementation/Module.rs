@@ -0,0 +1,291 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, Statement::Statement},
+    utils::tokenizer::tokenize,
+};
+
+use super::{
+    Environment::Environment,
+    Interpreter::{Interpreter, SharedEnv},
+    Parser::Parser,
+    Resolver::{ClassType, FunctionType, Resolver},
+    Token::Token,
+};
+
+/// An imported file, as bound by one `import`. Its top-level definitions
+/// live in `globals`, which is what `module.name` reads from; every import
+/// of the same file shares them but keeps its own `name`.
+pub struct LoxModule {
+    pub name: String,
+    pub path: PathBuf,
+    pub globals: SharedEnv,
+}
+
+/// A module an `import` has to run before it can bind it.
+pub struct ModuleSource {
+    /// The module's canonical path.
+    pub path: PathBuf,
+    /// The file id its spans carry.
+    pub file: u32,
+    pub statements: Vec<Box<dyn Statement>>,
+    /// The resolved depths of its local variables, for the tree-walker.
+    pub locals: HashMap<u32, usize>,
+}
+
+/// What an `import` found.
+pub enum Import<M> {
+    /// The module finished loading earlier.
+    Loaded(Rc<M>),
+    /// The module is new and still has to run.
+    Load(ModuleSource),
+}
+
+/// Everything a runtime knows about imported files. `M` is the runtime's
+/// module value: [`LoxModule`] for the interpreter and
+/// [`vm::Object::Module`](crate::vm::Object::Module) for the bytecode VM.
+pub struct ModuleRegistry<M = LoxModule> {
+    /// The file relative imports in the main script resolve against.
+    pub script_path: Option<PathBuf>,
+    /// Modules that finished loading, by canonical path.
+    cache: HashMap<PathBuf, Rc<M>>,
+    /// Modules whose top level is still running, outermost first. Importing
+    /// one of these again is a cycle.
+    loading: Vec<PathBuf>,
+    /// The errors after the first in a module that failed to tokenize,
+    /// parse or resolve. Only the first can unwind through `interpret`, so
+    /// the rest wait here for whoever reports it.
+    errors: Vec<LoxError>,
+    /// Path and text of every module ever loaded and every REPL input; a
+    /// span's `file` id is an index into this, plus one.
+    sources: Vec<(PathBuf, String)>,
+}
+
+impl<M> Default for ModuleRegistry<M> {
+    fn default() -> Self {
+        ModuleRegistry {
+            script_path: None,
+            cache: HashMap::new(),
+            loading: Vec::new(),
+            errors: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl<M> ModuleRegistry<M> {
+    /// The path and text of the module `error` was raised in, or `None` for
+    /// errors in the main script.
+    pub fn source_of(&self, error: &LoxError) -> Option<(&Path, &str)> {
+        let file = error.file() as usize;
+        let (path, source) = self.sources.get(file.checked_sub(1)?)?;
+        Some((path.as_path(), source.as_str()))
+    }
+
+    /// Keeps `source` for error reporting and returns the file id its spans
+    /// should carry.
+    pub fn add_source(&mut self, path: PathBuf, source: String) -> u32 {
+        self.sources.push((path, source));
+        self.sources.len() as u32
+    }
+
+    /// The errors that came with the one a failed import returned.
+    pub fn take_errors(&mut self) -> Vec<LoxError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Returns the first of a module's static errors to unwind with and
+    /// keeps the rest for [`ModuleRegistry::take_errors`].
+    pub fn set_aside(&mut self, mut errors: Vec<LoxError>) -> LoxError {
+        let first = errors.remove(0);
+        self.errors = errors;
+        first
+    }
+
+    /// Finds the module `path` names, relative to the importing file, and
+    /// reads, parses and resolves it unless it was loaded before. `error`
+    /// turns a message into an error raised by the `import` itself.
+    pub fn import(
+        &mut self,
+        path: &str,
+        error: impl Fn(String) -> LoxError,
+    ) -> Result<Import<M>, LoxError> {
+        let joined = self.base_directory().join(path);
+        let canonical = fs::canonicalize(&joined)
+            .map_err(|err| error(format!("Can't open module '{}': {}.", path, err)))?;
+        if let Some(module) = self.cache.get(&canonical) {
+            return Ok(Import::Loaded(Rc::clone(module)));
+        }
+        if let Some(start) = self.loading.iter().position(|p| *p == canonical) {
+            let cycle: Vec<String> = self.loading[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|p| {
+                    p.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect();
+            return Err(
+                error(format!("Import cycle detected: {}.", cycle.join(" -> ")))
+                    .with_note("a module can't import a module that is still loading it"),
+            );
+        }
+
+        let source = fs::read_to_string(&canonical)
+            .map_err(|err| error(format!("Can't read module '{}': {}.", path, err)))?;
+        let file = self.add_source(canonical.clone(), source.clone());
+        let module =
+            front_end(canonical, &source, file).map_err(|errors| self.set_aside(errors))?;
+        Ok(Import::Load(module))
+    }
+
+    /// Marks the module at `path` as running, so imports inside it resolve
+    /// against its directory and importing it again is a cycle.
+    pub fn enter(&mut self, path: PathBuf) {
+        self.loading.push(path);
+    }
+
+    /// Ends the innermost [`ModuleRegistry::enter`], caching `module` if the
+    /// module ran to completion.
+    pub fn leave(&mut self, module: Option<Rc<M>>) {
+        let path = self.loading.pop().expect("no module is loading");
+        if let Some(module) = module {
+            self.cache.insert(path, module);
+        }
+    }
+
+    /// Forgets the imports that were in progress when an error unwound them.
+    pub fn reset(&mut self) {
+        self.loading.clear();
+        self.errors.clear();
+    }
+
+    fn base_directory(&self) -> PathBuf {
+        self.loading
+            .last()
+            .or(self.script_path.as_ref())
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+}
+
+/// Loads, runs and caches the module at `path`, which is relative to the
+/// importing file, and binds it as `name`. The module is tokenized, parsed
+/// and resolved on its own and runs in a fresh global scope that only
+/// shares the natives.
+pub fn load_module(
+    interpreter: &mut Interpreter,
+    keyword: &Token,
+    path: &str,
+    name: &str,
+) -> Result<Rc<LoxModule>, LoxError> {
+    let mut source = match interpreter
+        .modules
+        .import(path, |message| LoxError::runtime(keyword, message))?
+    {
+        Import::Loaded(module) => {
+            return Ok(Rc::new(LoxModule {
+                name: name.to_string(),
+                path: module.path.clone(),
+                globals: Rc::clone(&module.globals),
+            }))
+        }
+        Import::Load(source) => source,
+    };
+    interpreter.locals.extend(source.locals);
+
+    let globals = Rc::new(RefCell::new(Environment {
+        values: HashMap::new(),
+        enclosing: Some(Rc::new(RefCell::new(natives(interpreter)))),
+    }));
+    let previous = std::mem::replace(&mut interpreter.environment, Rc::clone(&globals));
+    interpreter.modules.enter(source.path.clone());
+    let result = interpreter.interpret(&mut source.statements);
+    interpreter.environment = previous;
+    let module = result.map(|_| {
+        Rc::new(LoxModule {
+            name: name.to_string(),
+            path: source.path,
+            globals,
+        })
+    });
+    interpreter.modules.leave(module.as_ref().ok().cloned());
+    module
+}
+
+/// Tokenizes, parses and resolves a module's source. Its tokens are tagged
+/// with `file` so errors point into the right text, and the resolved
+/// variable depths are kept with the statements. Like
+/// [`Lox::parse_and_resolve`](super::Lox::Lox::parse_and_resolve), lex
+/// errors are reported together with the parse errors.
+fn front_end(path: PathBuf, source: &str, file: u32) -> Result<ModuleSource, Vec<LoxError>> {
+    let result = tokenize(source.to_string()).in_file(file);
+    let mut errors = result.errors;
+    let mut statements = match Parser::new(result.tokens).parse() {
+        Ok(statements) if errors.is_empty() => statements,
+        Ok(_) => return Err(errors),
+        Err(parse_errors) => {
+            errors.extend(parse_errors);
+            return Err(errors);
+        }
+    };
+
+    let mut resolver = Resolver {
+        scopes: Vec::from([HashMap::new()]),
+        interpreter: Box::new(Interpreter::default()),
+        current_function: FunctionType::NONE,
+        current_class: ClassType::NONE,
+        loop_depth: 0,
+    };
+    resolver
+        .resolve_statements(&mut statements)
+        .map_err(|error| vec![error])?;
+    let locals = std::mem::take(
+        &mut resolver
+            .interpreter
+            .as_any_mut()
+            .downcast_mut::<Interpreter>()
+            .expect("the module resolver records into an Interpreter")
+            .locals,
+    );
+    Ok(ModuleSource {
+        path,
+        file,
+        statements,
+        locals,
+    })
+}
+
+/// A scope holding the natives of the importing program, which becomes the
+/// parent of a module's globals.
+fn natives(interpreter: &Interpreter) -> Environment {
+    let mut root = Rc::clone(&interpreter.environment);
+    loop {
+        let parent = root.borrow().enclosing.clone();
+        match parent {
+            Some(parent) => root = parent,
+            None => break,
+        }
+    }
+    let values = root
+        .borrow()
+        .values
+        .iter()
+        .filter(|(_, value)| matches!(value, LiteralValue::Native(_)))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    Environment {
+        values,
+        enclosing: None,
+    }
+}
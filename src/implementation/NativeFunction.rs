@@ -0,0 +1,152 @@
+use std::{
+    fmt,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    enums::LiteralValue::LiteralValue,
+    traits::LoxConvert::{FromLox, IntoNativeResult},
+};
+
+use super::Interpreter::Interpreter;
+
+/// How many arguments a native function takes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Arity {
+    FIXED(usize),
+    /// At least this many; any extra arguments are passed along too.
+    VARIADIC(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::FIXED(arity) => count == arity,
+            Arity::VARIADIC(minimum) => count >= minimum,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arity::FIXED(arity) => write!(f, "{}", arity),
+            Arity::VARIADIC(minimum) => write!(f, "at least {}", minimum),
+        }
+    }
+}
+
+/// The Rust side of a native. An `Err` becomes a runtime error reported at
+/// the call site.
+pub type NativeFn = Rc<dyn Fn(&mut Interpreter, Vec<LiteralValue>) -> Result<LiteralValue, String>>;
+
+/// A function implemented in Rust and callable from Lox.
+#[derive(Clone)]
+pub struct NativeFunction {
+    pub name: String,
+    pub arity: Arity,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(
+        name: &str,
+        arity: Arity,
+        function: impl Fn(&mut Interpreter, Vec<LiteralValue>) -> Result<LiteralValue, String> + 'static,
+    ) -> NativeFunction {
+        NativeFunction {
+            name: name.to_string(),
+            arity,
+            function: Rc::new(function),
+        }
+    }
+}
+
+/// Rust functions whose parameters convert from Lox values and whose result
+/// converts back, so they can be registered without unpacking arguments by
+/// hand. Implemented for closures of up to five arguments; `Args` is the
+/// tuple of parameter types.
+pub trait HostFunction<Args> {
+    fn arity() -> usize;
+    fn call_with(&self, name: &str, arguments: Vec<LiteralValue>) -> Result<LiteralValue, String>;
+}
+
+fn convert_argument<T: FromLox>(
+    name: &str,
+    position: usize,
+    value: &LiteralValue,
+) -> Result<T, String> {
+    T::from_lox(value).ok_or_else(|| {
+        format!(
+            "Expected {} for argument {} of '{}' but got {}.",
+            T::expected(),
+            position,
+            name,
+            value.type_name()
+        )
+    })
+}
+
+macro_rules! host_function {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg),*> HostFunction<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> R,
+            R: IntoNativeResult,
+            $($arg: FromLox),*
+        {
+            fn arity() -> usize {
+                let parameters: &[&str] = &[$(stringify!($arg)),*];
+                parameters.len()
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut)]
+            fn call_with(
+                &self,
+                name: &str,
+                arguments: Vec<LiteralValue>,
+            ) -> Result<LiteralValue, String> {
+                let mut arguments = arguments.iter().enumerate();
+                $(
+                    let (index, value) = arguments.next().expect("arity is checked by the caller");
+                    let $arg = convert_argument::<$arg>(name, index + 1, value)?;
+                )*
+                self($($arg),*).into_native_result()
+            }
+        }
+    };
+}
+
+host_function!();
+host_function!(A);
+host_function!(A, B);
+host_function!(A, B, C);
+host_function!(A, B, C, D);
+host_function!(A, B, C, D, E);
+
+type Native = fn(&mut Interpreter, Vec<LiteralValue>) -> Result<LiteralValue, String>;
+
+/// The natives every interpreter defines as globals.
+pub const NATIVES: &[(&str, Arity, Native)] = &[
+    ("clock", Arity::FIXED(0), clock),
+    ("str", Arity::FIXED(1), str),
+];
+
+fn clock(_: &mut Interpreter, _: Vec<LiteralValue>) -> Result<LiteralValue, String> {
+    let since_the_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards");
+    Ok(LiteralValue::Number(since_the_epoch.as_secs_f64()))
+}
+
+/// `str(value)`: the text `print` would show for `value`.
+fn str(
+    interpreter: &mut Interpreter,
+    arguments: Vec<LiteralValue>,
+) -> Result<LiteralValue, String> {
+    interpreter
+        .stringify(&arguments[0])
+        .map(LiteralValue::String)
+        .map_err(|error| error.message().to_string())
+}
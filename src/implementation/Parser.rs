@@ -1,32 +1,43 @@
-use std::io::{self, Write};
 use std::result::Result::{Err, Ok};
 
 use rand::{rng, Rng};
 
+use crate::constants::constants::RESERVED_KEYWORDS_MAP;
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::enums::TokenType::TokenType;
 use crate::implementation::BinaryExpression::BinaryExpression;
 use crate::implementation::Grouping::Grouping;
 use crate::implementation::Literal::Literal;
-use crate::implementation::Token::Token;
+use crate::implementation::Token::{Span, Token};
 use crate::implementation::UnaryExpression::UnaryExpression;
 use crate::traits::Expression::Expression;
 use crate::traits::Statement::Statement;
 
 use super::AssignmentExpression::AssignmentExpression;
 use super::BlockStatement::BlockStatement;
+use super::BreakStatement::BreakStatement;
 use super::CallExpression::CallExpression;
 use super::ClassStatement::ClassStatement;
+use super::ContinueStatement::ContinueStatement;
 use super::ExpressionStatement::ExpressionStatement;
+use super::FunctionExpression::FunctionExpression;
 use super::FunctionStatement::FunctionStatement;
 use super::GetExpression::GetExpression;
 use super::IfStatement::IfStatement;
+use super::ImportStatement::ImportStatement;
+use super::IndexExpression::IndexExpression;
+use super::IndexSetExpression::IndexSetExpression;
+use super::ListExpression::ListExpression;
+use super::MapExpression::MapExpression;
 use super::LogicalExpression::LogicalExpression;
 use super::PrintStatement::PrintStatement;
 use super::ReturnStatement::ReturnStatement;
 use super::SetExpression::SetExpression;
 use super::SuperExpression::SuperExpression;
 use super::ThisExpression::ThisExpression;
+use super::ThrowStatement::ThrowStatement;
+use super::TryStatement::TryStatement;
 use super::VariableExpression::VariableExpression;
 use super::VariableStatement::VariableStatement;
 use super::WhileStatement::WhileStatement;
@@ -34,10 +45,19 @@ use super::WhileStatement::WhileStatement;
 pub struct Parser {
     pub tokens: Vec<Token>,
     pub current: usize,
+    errors: Vec<LoxError>,
 }
 
 impl Parser {
-    fn match_tokens(&mut self, token_types: &Vec<TokenType>) -> Result<bool, String> {
+    pub fn new(tokens: Vec<Token>) -> Parser {
+        Parser {
+            tokens,
+            current: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    fn match_tokens(&mut self, token_types: &Vec<TokenType>) -> Result<bool, LoxError> {
         for token_type in token_types {
             if self.check(*token_type)? {
                 self.advance()?;
@@ -47,39 +67,49 @@ impl Parser {
         return Ok(false);
     }
 
-    fn check(&self, token_type: TokenType) -> Result<bool, String> {
+    fn check(&self, token_type: TokenType) -> Result<bool, LoxError> {
         let token = self.peek()?;
         return Ok(token.token_type == token_type);
     }
 
-    fn previous(&self) -> Result<Token, String> {
+    fn previous(&self) -> Result<Token, LoxError> {
         match self.tokens.get(self.current - 1) {
             Some(token) => Ok(token.clone()),
-            None => Err(String::from("Token not found")),
+            None => Err(self.missing_token()),
         }
     }
 
-    fn peek(&self) -> Result<Token, String> {
+    fn peek(&self) -> Result<Token, LoxError> {
         match self.tokens.get(self.current) {
             Some(token) => Ok(token.clone()),
-            None => Err(String::from("Token not found")),
+            None => Err(self.missing_token()),
         }
     }
 
-    fn is_at_end(&self) -> Result<bool, String> {
+    fn missing_token(&self) -> LoxError {
+        let last = self.tokens.last().cloned().unwrap_or(Token {
+            token_type: TokenType::EOF,
+            token_value: String::from("EOF"),
+            line: 1,
+            span: Span::default(),
+        });
+        LoxError::parse(&last, String::from("Token not found"))
+    }
+
+    pub fn is_at_end(&self) -> Result<bool, LoxError> {
         match self.peek() {
             Ok(token) => Ok(token.token_type == TokenType::EOF),
             Err(err) => Err(err),
         }
     }
 
-    fn advance(&mut self) -> Result<Token, String> {
+    fn advance(&mut self) -> Result<Token, LoxError> {
         if !self.is_at_end()? {
             self.current += 1;
         }
         return self.previous();
     }
-    fn synchronize(&mut self) -> Result<(), String> {
+    fn synchronize(&mut self) -> Result<(), LoxError> {
         self.advance()?;
         while !self.is_at_end()? {
             if self.previous()?.token_type == TokenType::SEMICOLON {
@@ -94,6 +124,8 @@ impl Parser {
                 TokenType::WHILE => return Ok(()),
                 TokenType::PRINT => return Ok(()),
                 TokenType::RETURN => return Ok(()),
+                TokenType::TRY => return Ok(()),
+                TokenType::THROW => return Ok(()),
                 _ => {}
             }
             self.advance()?;
@@ -101,37 +133,26 @@ impl Parser {
         return Ok(());
     }
 
-    fn error(&mut self, token: Token, message: String) {
-        match token.token_type {
-            TokenType::EOF => writeln!(
-                io::stderr(),
-                "[line {}] Error at end: {}",
-                token.line,
-                message
-            )
-            .unwrap(),
-            _ => writeln!(
-                io::stderr(),
-                "[line {}] Error at '{}': {}",
-                token.line,
-                token.token_value,
-                message
-            )
-            .unwrap(),
-        }
-        self.synchronize();
-    }
-
-    fn consume(&mut self, token_type: TokenType, message: String) -> Result<Token, String> {
+    /// Records a syntax error and skips ahead to the next statement so
+    /// parsing can continue.
+    fn recover(&mut self, err: LoxError) -> Result<(), LoxError> {
+        self.errors.push(err);
+        self.synchronize()
+    }
+
+    fn error(&mut self, token: Token, message: String) -> LoxError {
+        LoxError::parse(&token, message)
+    }
+
+    fn consume(&mut self, token_type: TokenType, message: String) -> Result<Token, LoxError> {
         if self.check(token_type)? {
             return self.advance();
         }
 
-        self.error(self.peek()?, message);
-        return Err("An error occurred while consuming".to_string());
+        return Err(self.error(self.peek()?, message));
     }
 
-    fn primary(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn primary(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         if self.match_tokens(&[TokenType::FALSE].to_vec())? {
             return Ok(Box::new(Literal {
                 value: LiteralValue::Boolean(false),
@@ -155,8 +176,12 @@ impl Parser {
             }));
         } else if self.match_tokens(&[TokenType::NUMBER].to_vec())? {
             let token = self.previous()?;
+            let number = token
+                .token_value
+                .parse::<f64>()
+                .map_err(|err| LoxError::parse(&token, err.to_string()))?;
             return Ok(Box::new(Literal {
-                value: LiteralValue::Number(token.token_value),
+                value: LiteralValue::Number(number),
                 id: rng().random(),
             }));
         } else if self.match_tokens(&[TokenType::LEFT_PAREN].to_vec())? {
@@ -175,6 +200,62 @@ impl Parser {
                 }
                 Err(err) => Err(err),
             }
+        } else if self.match_tokens(&vec![TokenType::FUN])? {
+            let keyword = self.previous()?;
+            self.consume(
+                TokenType::LEFT_PAREN,
+                String::from("Expect '(' after 'fun'."),
+            )?;
+            let name = Token {
+                token_type: TokenType::IDENTIFIER,
+                token_value: String::from("anonymous"),
+                ..keyword.clone()
+            };
+            return Ok(Box::new(FunctionExpression {
+                keyword,
+                declaration: self.function_body(name, "function")?,
+                id: rng().random(),
+            }));
+        } else if self.match_tokens(&Vec::from([TokenType::LEFT_BRACKET]))? {
+            let bracket = self.previous()?;
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACKET)? {
+                elements.push(self.expression()?);
+                while self.match_tokens(&vec![TokenType::COMMA])? {
+                    elements.push(self.expression()?);
+                }
+            }
+            self.consume(
+                TokenType::RIGHT_BRACKET,
+                String::from("Expect ']' after list elements."),
+            )?;
+            return Ok(Box::new(ListExpression {
+                bracket,
+                elements,
+                id: rng().random(),
+            }));
+        } else if self.match_tokens(&Vec::from([TokenType::LEFT_BRACE]))? {
+            let brace = self.previous()?;
+            let mut entries = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACE)? {
+                loop {
+                    let key = self.expression()?;
+                    self.consume(TokenType::COLON, String::from("Expect ':' after map key."))?;
+                    entries.push((key, self.expression()?));
+                    if !self.match_tokens(&vec![TokenType::COMMA])? {
+                        break;
+                    }
+                }
+            }
+            self.consume(
+                TokenType::RIGHT_BRACE,
+                String::from("Expect '}' after map entries."),
+            )?;
+            return Ok(Box::new(MapExpression {
+                brace,
+                entries,
+                id: rng().random(),
+            }));
         } else if self.match_tokens(&Vec::from([TokenType::IDENTIFIER]))? {
             let token = self.previous()?;
             return Ok(Box::new(VariableExpression {
@@ -201,21 +282,20 @@ impl Parser {
             }));
         } else {
             let token = self.peek()?;
-            self.error(token, "Expect expression.".to_string());
-            return Err("Expect expression.".to_string());
+            return Err(self.error(token, "Expect expression.".to_string()));
         }
     }
 
-    fn finish_call(&mut self, callee: Box<dyn Expression>) -> Result<Box<dyn Expression>, String> {
+    fn finish_call(&mut self, callee: Box<dyn Expression>) -> Result<Box<dyn Expression>, LoxError> {
         let mut arguments = Vec::new();
         if !self.check(TokenType::RIGHT_PAREN)? {
             arguments.push(self.expression()?);
             while self.match_tokens(&vec![TokenType::COMMA])? {
                 if arguments.len() >= 255 {
-                    self.error(
+                    return Err(self.error(
                         self.peek()?,
                         String::from("Can't have more than 255 arguments."),
-                    )
+                    ));
                 }
                 arguments.push(self.expression()?);
             }
@@ -232,7 +312,7 @@ impl Parser {
         }));
     }
 
-    fn call(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn call(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         let mut expression = self.primary()?;
         let mut i = 0;
         loop {
@@ -248,6 +328,18 @@ impl Parser {
                     name,
                     id: rng().random(),
                 })
+            } else if self.match_tokens(&vec![TokenType::LEFT_BRACKET])? {
+                let index = self.expression()?;
+                let bracket = self.consume(
+                    TokenType::RIGHT_BRACKET,
+                    String::from("Expect ']' after index."),
+                )?;
+                expression = Box::new(IndexExpression {
+                    expression,
+                    bracket,
+                    index,
+                    id: rng().random(),
+                })
             } else {
                 break;
             }
@@ -256,7 +348,7 @@ impl Parser {
         return Ok(expression);
     }
 
-    fn unary(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn unary(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         if self.match_tokens(&[TokenType::BANG, TokenType::MINUS].to_vec())? {
             let operator = self.previous()?;
             match self.unary() {
@@ -273,7 +365,7 @@ impl Parser {
         return self.call();
     }
 
-    fn factor(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn factor(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         match self.unary() {
             Ok(mut expression) => {
                 while self.match_tokens(&[TokenType::SLASH, TokenType::STAR].to_vec())? {
@@ -296,7 +388,7 @@ impl Parser {
         }
     }
 
-    fn term(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn term(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         match self.factor() {
             Ok(mut expression) => {
                 while self.match_tokens(&[TokenType::MINUS, TokenType::PLUS].to_vec())? {
@@ -319,7 +411,7 @@ impl Parser {
         }
     }
 
-    fn comparison(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn comparison(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         match self.term() {
             Ok(mut expression) => {
                 while self.match_tokens(
@@ -350,7 +442,7 @@ impl Parser {
         }
     }
 
-    fn equality(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn equality(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         match self.comparison() {
             Ok(mut expression) => {
                 while self
@@ -376,7 +468,7 @@ impl Parser {
         }
     }
 
-    fn and(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn and(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         let mut expr = self.equality()?;
         while self.match_tokens(&vec![TokenType::AND])? {
             let operator = self.previous()?;
@@ -391,7 +483,7 @@ impl Parser {
         return Ok(expr);
     }
 
-    fn or(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn or(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         let mut expr = self.and()?;
         while self.match_tokens(&vec![TokenType::OR])? {
             let operator = self.previous()?;
@@ -406,7 +498,7 @@ impl Parser {
         return Ok(expr);
     }
 
-    fn assignment(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn assignment(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         let expression = self.or()?;
         if self.match_tokens(&Vec::from([TokenType::EQUAL]))? {
             let equals = self.previous()?;
@@ -426,17 +518,43 @@ impl Parser {
                     value,
                     id: rng().random(),
                 }));
+            } else if let Some(index_expr) = expression.as_any().downcast_ref::<IndexExpression>() {
+                return Ok(Box::new(IndexSetExpression {
+                    expression: index_expr.expression.clone_box(),
+                    bracket: index_expr.bracket.clone(),
+                    index: index_expr.index.clone_box(),
+                    value,
+                    id: rng().random(),
+                }));
             }
-            self.error(equals.clone(), String::from("Invalid assignment target."))
+            return Err(self
+                .error(equals.clone(), String::from("Invalid assignment target."))
+                .with_note("only variables, fields and list elements can be assigned to"));
         }
         return Ok(expression);
     }
 
-    pub fn expression(&mut self) -> Result<Box<dyn Expression>, String> {
+    fn expression(&mut self) -> Result<Box<dyn Expression>, LoxError> {
         return self.assignment();
     }
 
-    fn expression_statement(&mut self) -> Result<ExpressionStatement, String> {
+    /// Parses a single expression, the way `parse` does a whole program.
+    /// Errors recovered from inside it, e.g. a bad statement in the body of
+    /// an anonymous function, are reported too.
+    pub fn parse_expression(&mut self) -> Result<Box<dyn Expression>, Vec<LoxError>> {
+        let result = self.expression();
+        let mut errors = std::mem::take(&mut self.errors);
+        match result {
+            Ok(expression) if errors.is_empty() => Ok(expression),
+            Ok(_) => Err(errors),
+            Err(err) => {
+                errors.push(err);
+                Err(errors)
+            }
+        }
+    }
+
+    fn expression_statement(&mut self) -> Result<ExpressionStatement, LoxError> {
         let expression = self.expression()?;
         match self.consume(
             TokenType::SEMICOLON,
@@ -447,7 +565,7 @@ impl Parser {
         }
     }
 
-    fn while_statement(&mut self) -> Result<WhileStatement, String> {
+    fn while_statement(&mut self) -> Result<WhileStatement, LoxError> {
         self.consume(
             TokenType::LEFT_PAREN,
             String::from("Expect '(' after 'while'."),
@@ -458,10 +576,14 @@ impl Parser {
             String::from("Expect ')' after condition."),
         )?;
         let body = self.statement()?;
-        return Ok(WhileStatement { condition, body });
+        return Ok(WhileStatement {
+            condition,
+            body,
+            increment: None,
+        });
     }
 
-    fn for_statement(&mut self) -> Result<Box<dyn Statement>, String> {
+    fn for_statement(&mut self) -> Result<Box<dyn Statement>, LoxError> {
         self.consume(
             TokenType::LEFT_PAREN,
             String::from("Expect '(' after 'for'."),
@@ -491,14 +613,6 @@ impl Parser {
             String::from("Expect ')' after for clauses."),
         )?;
         let mut body = self.statement()?;
-        match increment {
-            Some(inc) => {
-                body = Box::new(BlockStatement {
-                    statements: vec![body, Box::new(ExpressionStatement { expression: inc })],
-                })
-            }
-            None => {}
-        }
         match condition {
             Some(_) => {}
             None => {
@@ -511,6 +625,7 @@ impl Parser {
         body = Box::new(WhileStatement {
             body,
             condition: condition.unwrap(),
+            increment,
         });
         match initializer {
             Some(init) => {
@@ -523,21 +638,28 @@ impl Parser {
         return Ok(body);
     }
 
-    fn print_statement(&mut self) -> Result<PrintStatement, String> {
+    fn print_statement(&mut self) -> Result<PrintStatement, LoxError> {
+        let keyword = self.previous()?;
         let expression = self.expression()?;
         match self.consume(
             TokenType::SEMICOLON,
             String::from("Expect ';' after value."),
         ) {
-            Ok(_) => Ok(PrintStatement { expression }),
+            Ok(_) => Ok(PrintStatement {
+                keyword,
+                expression,
+            }),
             Err(error) => Err(error),
         }
     }
 
-    fn block(&mut self) -> Result<BlockStatement, String> {
+    fn block(&mut self) -> Result<BlockStatement, LoxError> {
         let mut statements = Vec::new();
         while !self.check(TokenType::RIGHT_BRACE)? && !self.is_at_end()? {
-            statements.push(self.declaration()?);
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(err) => self.recover(err)?,
+            }
         }
         self.consume(
             TokenType::RIGHT_BRACE,
@@ -546,7 +668,7 @@ impl Parser {
         return Ok(BlockStatement { statements });
     }
 
-    fn if_statement(&mut self) -> Result<IfStatement, String> {
+    fn if_statement(&mut self) -> Result<IfStatement, LoxError> {
         self.consume(
             TokenType::LEFT_PAREN,
             String::from("Expect '(' after 'if'."),
@@ -569,7 +691,132 @@ impl Parser {
         });
     }
 
-    fn return_statement(&mut self) -> Result<ReturnStatement, String> {
+    fn import_statement(&mut self) -> Result<ImportStatement, LoxError> {
+        let keyword = self.previous()?;
+        let path = self.consume(
+            TokenType::STRING,
+            String::from("Expect module path string after 'import'."),
+        )?;
+        let name = if self.check(TokenType::IDENTIFIER)? && self.peek()?.token_value == "as" {
+            self.advance()?;
+            self.consume(
+                TokenType::IDENTIFIER,
+                String::from("Expect module name after 'as'."),
+            )?
+        } else {
+            let stem = std::path::Path::new(&path.token_value)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut chars = stem.chars();
+            let is_identifier = chars
+                .next()
+                .is_some_and(|first| first.is_alphabetic() || first == '_')
+                && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+                && !RESERVED_KEYWORDS_MAP.contains_key(stem.as_str());
+            if !is_identifier {
+                return Err(self
+                    .error(
+                        path,
+                        format!("Can't name a module '{}'.", stem),
+                    )
+                    .with_note("give the module a name with `import \"...\" as name;`"));
+            }
+            Token {
+                token_type: TokenType::IDENTIFIER,
+                token_value: stem,
+                ..path.clone()
+            }
+        };
+        self.consume(
+            TokenType::SEMICOLON,
+            String::from("Expect ';' after import."),
+        )?;
+        Ok(ImportStatement {
+            keyword,
+            path,
+            name,
+        })
+    }
+
+    fn throw_statement(&mut self) -> Result<ThrowStatement, LoxError> {
+        let keyword = self.previous()?;
+        let value = self.expression()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            String::from("Expect ';' after thrown value."),
+        )?;
+        Ok(ThrowStatement { keyword, value })
+    }
+
+    fn try_statement(&mut self) -> Result<TryStatement, LoxError> {
+        let keyword = self.previous()?;
+        self.consume(
+            TokenType::LEFT_BRACE,
+            String::from("Expect '{' after 'try'."),
+        )?;
+        let body = self.block()?;
+        let mut catch_clause = None;
+        if self.match_tokens(&vec![TokenType::CATCH])? {
+            self.consume(
+                TokenType::LEFT_PAREN,
+                String::from("Expect '(' after 'catch'."),
+            )?;
+            let name = self.consume(
+                TokenType::IDENTIFIER,
+                String::from("Expect error variable name."),
+            )?;
+            self.consume(
+                TokenType::RIGHT_PAREN,
+                String::from("Expect ')' after error variable."),
+            )?;
+            self.consume(
+                TokenType::LEFT_BRACE,
+                String::from("Expect '{' before catch body."),
+            )?;
+            catch_clause = Some((name, self.block()?));
+        }
+        let mut finally_block = None;
+        if self.match_tokens(&vec![TokenType::FINALLY])? {
+            self.consume(
+                TokenType::LEFT_BRACE,
+                String::from("Expect '{' after 'finally'."),
+            )?;
+            finally_block = Some(self.block()?);
+        }
+        if catch_clause.is_none() && finally_block.is_none() {
+            return Err(self.error(
+                self.peek()?,
+                String::from("Expect 'catch' or 'finally' after try block."),
+            ));
+        }
+        Ok(TryStatement {
+            keyword,
+            body,
+            catch_clause,
+            finally_block,
+        })
+    }
+
+    fn break_statement(&mut self) -> Result<BreakStatement, LoxError> {
+        let keyword = self.previous()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            String::from("Expect ';' after 'break'."),
+        )?;
+        Ok(BreakStatement { keyword })
+    }
+
+    fn continue_statement(&mut self) -> Result<ContinueStatement, LoxError> {
+        let keyword = self.previous()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            String::from("Expect ';' after 'continue'."),
+        )?;
+        Ok(ContinueStatement { keyword })
+    }
+
+    fn return_statement(&mut self) -> Result<ReturnStatement, LoxError> {
         let keyword = self.previous()?;
         let mut value: Option<Box<dyn Expression>> = None;
         if !self.check(TokenType::SEMICOLON)? {
@@ -582,7 +829,7 @@ impl Parser {
         Ok(ReturnStatement { keyword, value })
     }
 
-    fn statement(&mut self) -> Result<Box<dyn Statement>, String> {
+    fn statement(&mut self) -> Result<Box<dyn Statement>, LoxError> {
         if self.match_tokens(&vec![TokenType::PRINT])? {
             Ok(Box::new(self.print_statement()?))
         } else if self.match_tokens(&vec![TokenType::IF])? {
@@ -595,22 +842,32 @@ impl Parser {
             return Ok(self.for_statement()?);
         } else if self.match_tokens(&vec![TokenType::RETURN])? {
             return Ok(Box::new(self.return_statement()?));
+        } else if self.match_tokens(&vec![TokenType::IMPORT])? {
+            return Ok(Box::new(self.import_statement()?));
+        } else if self.match_tokens(&vec![TokenType::THROW])? {
+            return Ok(Box::new(self.throw_statement()?));
+        } else if self.match_tokens(&vec![TokenType::TRY])? {
+            return Ok(Box::new(self.try_statement()?));
+        } else if self.match_tokens(&vec![TokenType::BREAK])? {
+            return Ok(Box::new(self.break_statement()?));
+        } else if self.match_tokens(&vec![TokenType::CONTINUE])? {
+            return Ok(Box::new(self.continue_statement()?));
         } else {
             Ok(Box::new(self.expression_statement()?))
         }
     }
 
-    fn var_declaration(&mut self) -> Result<Box<dyn Statement>, String> {
+    fn var_declaration(&mut self) -> Result<Box<dyn Statement>, LoxError> {
         let name = self.consume(TokenType::IDENTIFIER, String::from("Expect variable name."))?;
 
         let mut initializer = None;
         if self.match_tokens(&Vec::from([TokenType::EQUAL]))? {
             initializer = Some(self.expression()?);
         }
-        let _ = self.consume(
+        self.consume(
             TokenType::SEMICOLON,
             String::from("Expect ';' after variable declaration."),
-        );
+        )?;
         match initializer {
             Some(v) => Ok(Box::new(VariableStatement {
                 initializer: v,
@@ -626,7 +883,7 @@ impl Parser {
         }
     }
 
-    fn class_declaration(&mut self) -> Result<Box<dyn Statement>, String> {
+    fn class_declaration(&mut self) -> Result<Box<dyn Statement>, LoxError> {
         let class_name = self.consume(TokenType::IDENTIFIER, String::from("Expect class name."))?;
         let mut super_class = None;
         if self.match_tokens(&vec![TokenType::LESS])? {
@@ -644,8 +901,25 @@ impl Parser {
             String::from("Expect '{' before class body."),
         )?;
         let mut methods = Vec::new();
+        let mut static_methods = Vec::new();
+        let mut setters = Vec::new();
         while !self.is_at_end()? && !self.check(TokenType::RIGHT_BRACE)? {
-            methods.push(self.fun_declaration("method".to_string())?);
+            // `static` is only special in front of a method name, so a method
+            // may still be called `static`.
+            let modifier = self.peek()?.token_value;
+            let is_modified = self
+                .tokens
+                .get(self.current + 1)
+                .is_some_and(|next| next.token_type == TokenType::IDENTIFIER);
+            if is_modified && modifier == "static" {
+                self.advance()?;
+                static_methods.push(self.fun_declaration("method".to_string())?);
+            } else if is_modified && modifier == "set" {
+                self.advance()?;
+                setters.push(self.setter_declaration()?);
+            } else {
+                methods.push(self.fun_declaration("method".to_string())?);
+            }
         }
         self.consume(
             TokenType::RIGHT_BRACE,
@@ -654,20 +928,57 @@ impl Parser {
         return Ok(Box::new(ClassStatement {
             name: class_name,
             methods,
+            static_methods,
+            setters,
             super_class,
         }));
     }
 
-    fn fun_declaration(&mut self, kind: String) -> Result<Box<dyn Statement>, String> {
+    /// Parses `name(value) { ... }` after a `set` modifier.
+    fn setter_declaration(&mut self) -> Result<Box<dyn Statement>, LoxError> {
+        let name = self.consume(TokenType::IDENTIFIER, String::from("Expect setter name."))?;
+        self.consume(
+            TokenType::LEFT_PAREN,
+            String::from("Expect '(' after setter name."),
+        )?;
+        let setter = self.function_body(name, "setter")?;
+        if setter.parameters.len() != 1 {
+            return Err(self.error(
+                setter.name,
+                String::from("A setter must take exactly one parameter."),
+            ));
+        }
+        return Ok(Box::new(setter));
+    }
+
+    fn fun_declaration(&mut self, kind: String) -> Result<Box<dyn Statement>, LoxError> {
         let name: Token = self.consume(
             TokenType::IDENTIFIER,
             String::from(format!("Expect {} name.", kind)),
         )?;
+        // A method without a parameter list is a getter, run on access.
+        if kind == "method" && self.match_tokens(&vec![TokenType::LEFT_BRACE])? {
+            if name.token_value == "init" {
+                return Err(self.error(name, String::from("An initializer can't be a getter.")));
+            }
+            let statements = self.block()?;
+            return Ok(Box::new(FunctionStatement {
+                name,
+                parameters: Vec::new(),
+                body: statements.statements,
+                is_getter: true,
+            }));
+        }
         self.consume(
             TokenType::LEFT_PAREN,
             String::from(format!("Expect '(' after {} name.", kind)),
         )?;
+        return Ok(Box::new(self.function_body(name, &kind)?));
+    }
 
+    /// Parses a function's parameter list and body, starting just after the
+    /// opening `(`.
+    fn function_body(&mut self, name: Token, kind: &str) -> Result<FunctionStatement, LoxError> {
         let mut parameters = Vec::new();
         if !self.check(TokenType::RIGHT_PAREN)? {
             parameters.push(self.consume(
@@ -677,10 +988,10 @@ impl Parser {
 
             while self.match_tokens(&vec![TokenType::COMMA])? {
                 if parameters.len() >= 255 {
-                    self.error(
+                    return Err(self.error(
                         self.peek()?,
                         String::from("Can't have more than 255 parameters"),
-                    );
+                    ));
                 }
                 parameters.push(self.consume(
                     TokenType::IDENTIFIER,
@@ -700,18 +1011,25 @@ impl Parser {
 
         let statements = self.block()?;
 
-        return Ok(Box::new(FunctionStatement {
+        return Ok(FunctionStatement {
             name,
             parameters,
             body: statements.statements,
-        }));
+            is_getter: false,
+        });
     }
 
-    fn declaration(&mut self) -> Result<Box<dyn Statement>, String> {
+    fn declaration(&mut self) -> Result<Box<dyn Statement>, LoxError> {
         if self.match_tokens(&Vec::from([TokenType::VAR]))? {
             return self.var_declaration();
         }
-        if self.match_tokens(&vec![TokenType::FUN])? {
+        // `fun (` starts an anonymous function expression, not a declaration.
+        let is_named = self
+            .tokens
+            .get(self.current + 1)
+            .is_some_and(|next| next.token_type != TokenType::LEFT_PAREN);
+        if self.check(TokenType::FUN)? && is_named {
+            self.advance()?;
             return self.fun_declaration(String::from("function"));
         }
         if self.match_tokens(&vec![TokenType::CLASS])? {
@@ -720,12 +1038,25 @@ impl Parser {
         return self.statement();
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Box<dyn Statement>>, String> {
+    /// Parses the whole program. After a syntax error the parser skips to the
+    /// next statement boundary and keeps going, so every error is reported.
+    pub fn parse(&mut self) -> Result<Vec<Box<dyn Statement>>, Vec<LoxError>> {
         let mut statements = Vec::new();
-        while !self.is_at_end()? {
-            statements.push(self.declaration()?);
+        while let Ok(false) = self.is_at_end() {
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(err) => {
+                    if let Err(err) = self.recover(err) {
+                        self.errors.push(err);
+                        break;
+                    }
+                }
+            }
         }
 
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
         return Ok(statements);
     }
 }
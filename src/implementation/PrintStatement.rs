@@ -1,17 +1,21 @@
 use std::any::Any;
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
     traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
 };
 
+use super::Token::Token;
+
 pub struct PrintStatement {
+    pub keyword: Token,
     pub expression: Box<dyn Expression>,
 }
 
 impl Clone for PrintStatement {
     fn clone(&self) -> Self {
         PrintStatement {
+            keyword: self.keyword.clone(),
             expression: self.expression.clone_box(),
         }
     }
@@ -24,12 +28,16 @@ impl Statement for PrintStatement {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return interpreter.visit_print_statement(self);
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_print_statement(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_print_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_print_statement(self);
     }
 
     fn clone_box(&self) -> Box<dyn Statement> {
@@ -1,22 +1,22 @@
-use std::{
-    collections::HashMap,
-    io::{self, Write},
-    process::exit,
-};
+use std::collections::HashMap;
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::LoxError::LoxError,
     traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
 };
 
 use super::{
     AssignmentExpression::AssignmentExpression, BinaryExpression::BinaryExpression,
-    BlockStatement::BlockStatement, CallExpression::CallExpression, ClassStatement::ClassStatement,
-    ExpressionStatement::ExpressionStatement, FunctionStatement::FunctionStatement,
-    GetExpression::GetExpression, Grouping::Grouping, IfStatement::IfStatement, Literal::Literal,
-    LogicalExpression::LogicalExpression, PrintStatement::PrintStatement,
-    ReturnStatement::ReturnStatement, SetExpression::SetExpression,
-    SuperExpression::SuperExpression, ThisExpression::ThisExpression, Token::Token,
+    BlockStatement::BlockStatement, BreakStatement::BreakStatement, CallExpression::CallExpression,
+    ClassStatement::ClassStatement, ContinueStatement::ContinueStatement,
+    ExpressionStatement::ExpressionStatement, FunctionExpression::FunctionExpression, FunctionStatement::FunctionStatement,
+    GetExpression::GetExpression, Grouping::Grouping, IfStatement::IfStatement,
+    ImportStatement::ImportStatement,
+    IndexExpression::IndexExpression, IndexSetExpression::IndexSetExpression,
+    ListExpression::ListExpression, Literal::Literal, LogicalExpression::LogicalExpression,
+    MapExpression::MapExpression, PrintStatement::PrintStatement, ReturnStatement::ReturnStatement,
+    SetExpression::SetExpression, SuperExpression::SuperExpression, ThisExpression::ThisExpression,
+    ThrowStatement::ThrowStatement, Token::Token, TryStatement::TryStatement,
     UnaryExpression::UnaryExpression, VariableExpression::VariableExpression,
     VariableStatement::VariableStatement, WhileStatement::WhileStatement,
 };
@@ -41,29 +41,45 @@ pub struct Resolver {
     pub scopes: Vec<HashMap<String, bool>>,
     pub current_function: FunctionType,
     pub current_class: ClassType,
+    /// How many loops enclose the code being resolved, counted from the
+    /// innermost function body.
+    pub loop_depth: usize,
 }
 
 impl Resolver {
-    pub fn visit_block_statement(&mut self, statement: &mut BlockStatement) {
+    pub fn visit_block_statement(
+        &mut self,
+        statement: &mut BlockStatement,
+    ) -> Result<(), LoxError> {
         self.begin_scope();
-        self.resolve_statements(&mut statement.statements);
+        self.resolve_statements(&mut statement.statements)?;
         self.end_scope();
+        Ok(())
     }
 
-    pub fn visit_variable_statement(&mut self, statement: &mut VariableStatement) {
-        self.declare(&statement.name);
-        statement.initializer.resolve(self);
+    pub fn visit_variable_statement(
+        &mut self,
+        statement: &mut VariableStatement,
+    ) -> Result<(), LoxError> {
+        self.declare(&statement.name)?;
+        statement.initializer.resolve(self)?;
         self.define(&statement.name);
+        Ok(())
     }
 
-    pub fn visit_variable_expression(&mut self, expression: &mut VariableExpression) {
+    pub fn visit_variable_expression(
+        &mut self,
+        expression: &mut VariableExpression,
+    ) -> Result<(), LoxError> {
         if let Some(scope) = self.scopes.last_mut() {
             if let Some(v) = scope.get(&expression.variable.token_value) {
                 if *v == false && self.scopes.len() > 1 {
-                    self.error(
-                        String::from("Can't read local variable in its own initializer."),
-                        &expression.variable,
-                    );
+                    return Err(self
+                        .error(
+                            String::from("Can't read local variable in its own initializer."),
+                            &expression.variable,
+                        )
+                        .with_note("the new variable only exists once its initializer has run"));
                 }
             }
         }
@@ -71,113 +87,272 @@ impl Resolver {
         let variable_token = expression.variable.clone();
 
         self.resolve_local(expression, variable_token);
+        Ok(())
     }
 
-    pub fn visit_assignment_expression(&mut self, expression: &mut AssignmentExpression) {
-        expression.value.resolve(self);
+    pub fn visit_assignment_expression(
+        &mut self,
+        expression: &mut AssignmentExpression,
+    ) -> Result<(), LoxError> {
+        expression.value.resolve(self)?;
         let name = expression.name.clone();
         self.resolve_local(expression, name);
+        Ok(())
+    }
+
+    pub fn visit_get_expression(&mut self, expression: &mut GetExpression) -> Result<(), LoxError> {
+        expression.expression.resolve(self)
+    }
+
+    pub fn visit_set_expression(&mut self, expression: &mut SetExpression) -> Result<(), LoxError> {
+        expression.expression.resolve(self)?;
+        expression.value.resolve(self)
+    }
+
+    pub fn visit_list_expression(
+        &mut self,
+        expression: &mut ListExpression,
+    ) -> Result<(), LoxError> {
+        for element in &mut expression.elements {
+            element.resolve(self)?;
+        }
+        Ok(())
+    }
+
+    pub fn visit_function_expression(
+        &mut self,
+        expression: &mut FunctionExpression,
+    ) -> Result<(), LoxError> {
+        self.resolve_function(&mut expression.declaration, FunctionType::FUNCTION)
     }
 
-    pub fn visit_get_expression(&mut self, expression: &mut GetExpression) {
-        expression.expression.resolve(self);
+    pub fn visit_map_expression(&mut self, expression: &mut MapExpression) -> Result<(), LoxError> {
+        for (key, value) in &mut expression.entries {
+            key.resolve(self)?;
+            value.resolve(self)?;
+        }
+        Ok(())
     }
 
-    pub fn visit_set_expression(&mut self, expression: &mut SetExpression) {
-        expression.expression.resolve(self);
-        expression.value.resolve(self);
+    pub fn visit_index_expression(
+        &mut self,
+        expression: &mut IndexExpression,
+    ) -> Result<(), LoxError> {
+        expression.expression.resolve(self)?;
+        expression.index.resolve(self)
     }
 
-    pub fn visit_function_statement(&mut self, statement: &mut FunctionStatement) {
-        self.declare(&statement.name);
+    pub fn visit_index_set_expression(
+        &mut self,
+        expression: &mut IndexSetExpression,
+    ) -> Result<(), LoxError> {
+        expression.expression.resolve(self)?;
+        expression.index.resolve(self)?;
+        expression.value.resolve(self)
+    }
+
+    pub fn visit_function_statement(
+        &mut self,
+        statement: &mut FunctionStatement,
+    ) -> Result<(), LoxError> {
+        self.declare(&statement.name)?;
         self.define(&statement.name);
-        self.resolve_function(statement, FunctionType::FUNCTION);
+        self.resolve_function(statement, FunctionType::FUNCTION)
     }
 
-    pub fn visit_expression_statement(&mut self, statement: &mut ExpressionStatement) {
-        statement.expression.resolve(self);
+    pub fn visit_expression_statement(
+        &mut self,
+        statement: &mut ExpressionStatement,
+    ) -> Result<(), LoxError> {
+        statement.expression.resolve(self)
     }
 
-    pub fn visit_if_statement(&mut self, statement: &mut IfStatement) {
-        statement.condition.resolve(self);
-        statement.then_statement.resolve(self);
+    pub fn visit_if_statement(&mut self, statement: &mut IfStatement) -> Result<(), LoxError> {
+        statement.condition.resolve(self)?;
+        statement.then_statement.resolve(self)?;
         if let Some(else_st) = &mut statement.else_statement {
-            else_st.resolve(self);
+            else_st.resolve(self)?;
         }
+        Ok(())
     }
 
-    pub fn visit_print_statement(&mut self, statement: &mut PrintStatement) {
-        statement.expression.resolve(self);
+    pub fn visit_print_statement(
+        &mut self,
+        statement: &mut PrintStatement,
+    ) -> Result<(), LoxError> {
+        statement.expression.resolve(self)
     }
 
-    pub fn visit_return_statement(&mut self, statement: &mut ReturnStatement) {
+    pub fn visit_return_statement(
+        &mut self,
+        statement: &mut ReturnStatement,
+    ) -> Result<(), LoxError> {
         if self.current_function == FunctionType::NONE {
-            self.error(
+            return Err(self.error(
                 String::from("Can't return from top-level code."),
                 &statement.keyword,
-            )
+            ));
         }
         if let Some(v) = &mut statement.value {
             if self.current_function == FunctionType::INITIALIZER {
-                self.error(
-                    String::from("Can't return a value from an initializer."),
-                    &statement.keyword,
-                );
+                return Err(self
+                    .error(
+                        String::from("Can't return a value from an initializer."),
+                        &statement.keyword,
+                    )
+                    .with_note("`init` always returns `this`; use a bare `return;`"));
             }
-            v.resolve(self);
+            v.resolve(self)?;
         }
+        Ok(())
     }
 
-    pub fn visit_while_statement(&mut self, statement: &mut WhileStatement) {
-        statement.body.resolve(self);
-        statement.condition.resolve(self);
+    pub fn visit_while_statement(
+        &mut self,
+        statement: &mut WhileStatement,
+    ) -> Result<(), LoxError> {
+        statement.condition.resolve(self)?;
+        self.loop_depth += 1;
+        let result = statement.body.resolve(self);
+        self.loop_depth -= 1;
+        result?;
+        if let Some(increment) = &mut statement.increment {
+            increment.resolve(self)?;
+        }
+        Ok(())
     }
 
-    pub fn visit_binary_expression(&mut self, expression: &mut BinaryExpression) {
-        expression.left.resolve(self);
-        expression.right.resolve(self);
+    pub fn visit_import_statement(
+        &mut self,
+        statement: &mut ImportStatement,
+    ) -> Result<(), LoxError> {
+        if self.scopes.len() > 1 || self.current_function != FunctionType::NONE {
+            return Err(self.error(
+                String::from("Can only import at the top level."),
+                &statement.keyword,
+            ));
+        }
+        self.declare(&statement.name)?;
+        self.define(&statement.name);
+        Ok(())
     }
 
-    pub fn visit_call_expression(&mut self, expression: &mut CallExpression) {
-        expression.callee.resolve(self);
+    pub fn visit_throw_statement(
+        &mut self,
+        statement: &mut ThrowStatement,
+    ) -> Result<(), LoxError> {
+        statement.value.resolve(self)
+    }
+
+    pub fn visit_try_statement(&mut self, statement: &mut TryStatement) -> Result<(), LoxError> {
+        self.visit_block_statement(&mut statement.body)?;
+        if let Some((name, handler)) = &mut statement.catch_clause {
+            // The error variable and the handler's locals share one scope.
+            self.begin_scope();
+            self.declare(name)?;
+            self.define(name);
+            self.resolve_statements(&mut handler.statements)?;
+            self.end_scope();
+        }
+        if let Some(finally_block) = &mut statement.finally_block {
+            self.visit_block_statement(finally_block)?;
+        }
+        Ok(())
+    }
+
+    pub fn visit_break_statement(
+        &mut self,
+        statement: &mut BreakStatement,
+    ) -> Result<(), LoxError> {
+        if self.loop_depth == 0 {
+            return Err(self.error(
+                String::from("Can't use 'break' outside of a loop."),
+                &statement.keyword,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn visit_continue_statement(
+        &mut self,
+        statement: &mut ContinueStatement,
+    ) -> Result<(), LoxError> {
+        if self.loop_depth == 0 {
+            return Err(self.error(
+                String::from("Can't use 'continue' outside of a loop."),
+                &statement.keyword,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn visit_binary_expression(
+        &mut self,
+        expression: &mut BinaryExpression,
+    ) -> Result<(), LoxError> {
+        expression.left.resolve(self)?;
+        expression.right.resolve(self)
+    }
+
+    pub fn visit_call_expression(
+        &mut self,
+        expression: &mut CallExpression,
+    ) -> Result<(), LoxError> {
+        expression.callee.resolve(self)?;
         for expr in &mut expression.arguments {
-            expr.resolve(self);
+            expr.resolve(self)?;
         }
+        Ok(())
     }
 
-    pub fn visit_grouping_expression(&mut self, expression: &mut Grouping) {
-        expression.expression.resolve(self);
+    pub fn visit_grouping_expression(&mut self, expression: &mut Grouping) -> Result<(), LoxError> {
+        expression.expression.resolve(self)
     }
 
-    pub fn visit_logical_expression(&mut self, expression: &mut LogicalExpression) {
-        expression.left.resolve(self);
-        expression.right.resolve(self);
+    pub fn visit_logical_expression(
+        &mut self,
+        expression: &mut LogicalExpression,
+    ) -> Result<(), LoxError> {
+        expression.left.resolve(self)?;
+        expression.right.resolve(self)
     }
 
-    pub fn visit_unary_expression(&mut self, expression: &mut UnaryExpression) {
-        expression.expression.resolve(self);
+    pub fn visit_unary_expression(
+        &mut self,
+        expression: &mut UnaryExpression,
+    ) -> Result<(), LoxError> {
+        expression.expression.resolve(self)
     }
 
-    pub fn visit_literal_expression(&mut self, _expression: &mut Literal) {}
-    pub fn visit_super_expression(&mut self, expression: &mut SuperExpression) {
+    pub fn visit_literal_expression(&mut self, _expression: &mut Literal) -> Result<(), LoxError> {
+        Ok(())
+    }
+    pub fn visit_super_expression(
+        &mut self,
+        expression: &mut SuperExpression,
+    ) -> Result<(), LoxError> {
         if self.current_class == ClassType::NONE {
-            self.error(
+            return Err(self.error(
                 String::from("Can't use 'super' outside of a class."),
                 &expression.keyword,
-            );
+            ));
         } else if self.current_class != ClassType::SUBCLASS {
-            self.error(
+            return Err(self.error(
                 String::from("Can't use 'super' in a class with no superclass."),
                 &expression.keyword,
-            );
+            ));
         }
         let token = expression.keyword.clone();
         self.resolve_local(expression, token);
+        Ok(())
     }
-    pub fn visit_class_statement(&mut self, statement: &mut ClassStatement) {
+    pub fn visit_class_statement(
+        &mut self,
+        statement: &mut ClassStatement,
+    ) -> Result<(), LoxError> {
         let prev = self.current_class.clone();
         self.current_class = ClassType::CLASS;
-        self.declare(&statement.name);
+        self.declare(&statement.name)?;
         self.define(&statement.name);
 
         if let Some(superclass) = &mut statement.super_class {
@@ -186,12 +361,12 @@ impl Resolver {
                 .token_value
                 .eq(&superclass.variable.token_value)
             {
-                self.error(
+                return Err(self.error(
                     String::from("A class can't inherit from itself."),
                     &superclass.variable,
-                );
+                ));
             }
-            self.visit_variable_expression(superclass);
+            self.visit_variable_expression(superclass)?;
         }
         if let Some(_) = &mut statement.super_class {
             self.begin_scope();
@@ -209,46 +384,71 @@ impl Resolver {
                     declaration = FunctionType::INITIALIZER;
                 }
 
-                self.resolve_function(method_fn, declaration);
+                self.resolve_function(method_fn, declaration)?;
             } else {
                 unreachable!("ClassStatement.methods must all be functions");
             }
         }
+        for method in &mut statement.static_methods {
+            if let Some(method_fn) = method.as_any_mut().downcast_mut::<FunctionStatement>() {
+                self.resolve_function(method_fn, FunctionType::METHOD)?;
+            } else {
+                unreachable!("ClassStatement.static_methods must all be functions");
+            }
+        }
+        for setter in &mut statement.setters {
+            if let Some(setter_fn) = setter.as_any_mut().downcast_mut::<FunctionStatement>() {
+                self.resolve_function(setter_fn, FunctionType::METHOD)?;
+            } else {
+                unreachable!("ClassStatement.setters must all be functions");
+            }
+        }
         self.end_scope();
         if let Some(_) = &mut statement.super_class {
             self.end_scope();
         }
         self.current_class = prev;
+        Ok(())
     }
 
-    pub fn visit_this_expression(&mut self, expression: &mut ThisExpression) {
+    pub fn visit_this_expression(
+        &mut self,
+        expression: &mut ThisExpression,
+    ) -> Result<(), LoxError> {
         if self.current_class == ClassType::NONE {
-            self.error(
+            return Err(self.error(
                 String::from("Can't use 'this' outside of a class."),
                 &expression.value,
-            );
+            ));
         }
         let token = expression.value.clone();
         self.resolve_local(expression, token);
+        Ok(())
     }
 
-    fn error(&self, message: String, token: &Token) {
-        writeln!(io::stderr(), "{}", message).unwrap();
-        writeln!(io::stderr(), "[line {}]", token.line).unwrap();
-        exit(65);
+    fn error(&self, message: String, token: &Token) -> LoxError {
+        LoxError::resolve(token, message)
     }
 
-    fn resolve_function(&mut self, statement: &mut FunctionStatement, ft: FunctionType) {
+    fn resolve_function(
+        &mut self,
+        statement: &mut FunctionStatement,
+        ft: FunctionType,
+    ) -> Result<(), LoxError> {
         let enclosing_function_type = self.current_function.clone();
+        let enclosing_loop_depth = std::mem::take(&mut self.loop_depth);
         self.current_function = ft;
         self.begin_scope();
         for prm in &statement.parameters {
-            self.declare(prm);
+            self.declare(prm)?;
             self.define(prm);
         }
-        self.resolve_statements(&mut statement.body);
+        let result = self.resolve_statements(&mut statement.body);
+        self.loop_depth = enclosing_loop_depth;
+        result?;
         self.end_scope();
         self.current_function = enclosing_function_type;
+        Ok(())
     }
 
     fn resolve_local(&mut self, expression: &mut dyn Expression, token: Token) {
@@ -259,18 +459,21 @@ impl Resolver {
         }
     }
 
-    fn declare(&mut self, name: &Token) {
+    fn declare(&mut self, name: &Token) -> Result<(), LoxError> {
         let scope_len = self.scopes.len();
         if let Some(scope) = self.scopes.last_mut() {
             if scope.contains_key(&name.token_value) && scope_len > 1 {
-                self.error(
-                    String::from("Already a variable with this name in this scope."),
-                    name,
-                );
+                return Err(self
+                    .error(
+                        String::from("Already a variable with this name in this scope."),
+                        name,
+                    )
+                    .with_note("drop the `var` to assign to the existing variable"));
             } else {
                 scope.insert(name.token_value.clone(), false);
             }
         }
+        Ok(())
     }
 
     fn define(&mut self, name: &Token) {
@@ -285,9 +488,22 @@ impl Resolver {
     fn end_scope(&mut self) {
         self.scopes.pop();
     }
-    pub fn resolve_statements(&mut self, statements: &mut Vec<Box<dyn Statement>>) {
+    pub fn resolve_statements(
+        &mut self,
+        statements: &mut Vec<Box<dyn Statement>>,
+    ) -> Result<(), LoxError> {
         for stm in statements {
-            stm.resolve(self);
+            stm.resolve(self)?;
         }
+        Ok(())
+    }
+
+    /// Drops any scopes left open by a resolution that failed part way, so the
+    /// resolver can be reused for the next input.
+    pub fn reset(&mut self) {
+        self.scopes.truncate(1);
+        self.current_function = FunctionType::NONE;
+        self.current_class = ClassType::NONE;
+        self.loop_depth = 0;
     }
 }
@@ -1,4 +1,4 @@
-use crate::{enums::LiteralValue::LiteralValue, traits::Expression::Expression};
+use crate::{enums::{LiteralValue::LiteralValue, LoxError::LoxError}, traits::Expression::Expression};
 
 use super::Token::Token;
 
@@ -33,13 +33,17 @@ impl Expression for SetExpression {
     fn id(&self) -> u32 {
         self.id
     }
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_set_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_set_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_set_expression(self);
     }
     fn interpret(
         &mut self,
         interpreter: &mut dyn crate::traits::Interpreter::InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_set_expression(self);
     }
 }
@@ -3,6 +3,7 @@ use std::any::Any;
 use rand::{rng, Rng};
 
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AstPrinter::AstPrinter;
 use crate::traits::AstPrinter::AstPrinterTrait;
 use crate::traits::Expression::Expression;
@@ -44,14 +45,18 @@ impl Expression for ThisExpression {
         self.id
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_this_expression(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_this_expression(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_this_expression(self);
     }
 
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<LiteralValue, String> {
+    ) -> Result<LiteralValue, LoxError> {
         return interpreter.visit_this_expression(self);
     }
     fn as_any(&self) -> &dyn Any {
@@ -0,0 +1,46 @@
+use std::any::Any;
+
+use crate::{
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
+    traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
+};
+
+use super::Token::Token;
+
+pub struct ThrowStatement {
+    pub keyword: Token,
+    pub value: Box<dyn Expression>,
+}
+
+impl Clone for ThrowStatement {
+    fn clone(&self) -> Self {
+        ThrowStatement {
+            keyword: self.keyword.clone(),
+            value: self.value.clone_box(),
+        }
+    }
+}
+
+impl Statement for ThrowStatement {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn InterpreterTrait,
+    ) -> Result<ControlFlow, LoxError> {
+        return interpreter.visit_throw_statement(self);
+    }
+
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_throw_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_throw_statement(self);
+    }
+
+    fn clone_box(&self) -> Box<dyn Statement> {
+        Box::new(self.clone())
+    }
+}
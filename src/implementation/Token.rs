@@ -1,8 +1,40 @@
-use crate::TokenType;
+use crate::enums::TokenType::TokenType;
+
+/// Where a token sits in the source. Offsets are byte indices into the
+/// source string (`end` exclusive); columns are 1-based character counts
+/// from the start of the line (`end_column` exclusive).
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub column: u32,
+    pub end_column: u32,
+    /// Which source the offsets refer to: 0 for the main script, otherwise
+    /// the id of an imported module or REPL input.
+    pub file: u32,
+}
+
+impl Span {
+    pub fn new(source: &str, start: usize, end: usize) -> Span {
+        Span {
+            start,
+            end,
+            column: column_at(source, start),
+            end_column: column_at(source, end),
+            file: 0,
+        }
+    }
+}
+
+fn column_at(source: &str, offset: usize) -> u32 {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    source[line_start..offset].chars().count() as u32 + 1
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub token_value: String,
     pub line: u32,
+    pub span: Span,
 }
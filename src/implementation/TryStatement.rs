@@ -0,0 +1,43 @@
+use std::any::Any;
+
+use crate::{
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
+    traits::{Interpreter::InterpreterTrait, Statement::Statement},
+};
+
+use super::{BlockStatement::BlockStatement, Token::Token};
+
+/// `try { } catch (e) { } finally { }`. At least one of the catch and
+/// finally clauses is present.
+#[derive(Clone)]
+pub struct TryStatement {
+    pub keyword: Token,
+    pub body: BlockStatement,
+    /// The variable bound to the caught error, and the handler's body.
+    pub catch_clause: Option<(Token, BlockStatement)>,
+    pub finally_block: Option<BlockStatement>,
+}
+
+impl Statement for TryStatement {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn interpret(
+        &mut self,
+        interpreter: &mut dyn InterpreterTrait,
+    ) -> Result<ControlFlow, LoxError> {
+        return interpreter.visit_try_statement(self);
+    }
+
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_try_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_try_statement(self);
+    }
+
+    fn clone_box(&self) -> Box<dyn Statement> {
+        Box::new(self.clone())
+    }
+}
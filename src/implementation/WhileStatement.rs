@@ -1,13 +1,16 @@
 use std::any::Any;
 
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::{ControlFlow::ControlFlow, LoxError::LoxError},
     traits::{Expression::Expression, Interpreter::InterpreterTrait, Statement::Statement},
 };
 
 pub struct WhileStatement {
     pub condition: Box<dyn Expression>,
     pub body: Box<dyn Statement>,
+    /// The increment clause of a desugared `for` loop. It runs after every
+    /// iteration, including ones cut short by `continue`.
+    pub increment: Option<Box<dyn Expression>>,
 }
 
 impl Clone for WhileStatement {
@@ -15,6 +18,7 @@ impl Clone for WhileStatement {
         WhileStatement {
             condition: self.condition.clone_box(),
             body: self.body.clone_box(),
+            increment: self.increment.as_ref().map(|increment| increment.clone_box()),
         }
     }
 }
@@ -26,12 +30,16 @@ impl Statement for WhileStatement {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String> {
+    ) -> Result<ControlFlow, LoxError> {
         return interpreter.visit_while_statement(self);
     }
 
-    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) {
-        resolver.visit_while_statement(self);
+    fn resolve(&mut self, resolver: &mut super::Resolver::Resolver) -> Result<(), LoxError> {
+        resolver.visit_while_statement(self)
+    }
+
+    fn compile(&mut self, compiler: &mut crate::compiler::Compiler::Compiler) {
+        compiler.visit_while_statement(self);
     }
 
     fn clone_box(&self) -> Box<dyn Statement> {
@@ -2,21 +2,33 @@ pub mod AssignmentExpression;
 pub mod AstPrinter;
 pub mod BinaryExpression;
 pub mod BlockStatement;
+pub mod BreakStatement;
 pub mod CallExpression;
 pub mod ClassStatement;
-pub mod Clock;
+pub mod ContinueStatement;
 pub mod Environment;
 pub mod ExpressionStatement;
+pub mod FunctionExpression;
 pub mod FunctionStatement;
 pub mod GetExpression;
 pub mod Grouping;
 pub mod IfStatement;
+pub mod ImportStatement;
+pub mod IndexExpression;
+pub mod IndexSetExpression;
 pub mod Interpreter;
+pub mod ListExpression;
+pub mod ListMethod;
 pub mod Literal;
 pub mod LogicalExpression;
+pub mod Lox;
 pub mod LoxClass;
 pub mod LoxFunction;
 pub mod LoxInstance;
+pub mod MapExpression;
+pub mod MapMethod;
+pub mod Module;
+pub mod NativeFunction;
 pub mod Parser;
 pub mod PrintStatement;
 pub mod Resolver;
@@ -24,7 +36,9 @@ pub mod ReturnStatement;
 pub mod SetExpression;
 pub mod SuperExpression;
 pub mod ThisExpression;
+pub mod ThrowStatement;
 pub mod Token;
+pub mod TryStatement;
 pub mod UnaryExpression;
 pub mod VariableExpression;
 pub mod VariableStatement;
@@ -0,0 +1,10 @@
+pub mod compiler;
+pub mod constants;
+pub mod enums;
+pub mod implementation;
+pub mod traits;
+pub mod utils;
+pub mod vm;
+
+pub use enums::LiteralValue::LiteralValue;
+pub use implementation::Lox::Lox;
@@ -1,4 +1,7 @@
-use crate::{enums::LiteralValue::LiteralValue, implementation::Resolver::Resolver};
+use crate::{
+    compiler::Compiler::Compiler, enums::{LiteralValue::LiteralValue, LoxError::LoxError},
+    implementation::Resolver::Resolver,
+};
 use std::any::Any;
 
 use crate::traits::Interpreter::InterpreterTrait;
@@ -7,8 +10,9 @@ pub trait Expression: Any {
     fn id(&self) -> u32;
     fn expression_print(&self) -> String;
     fn interpret(&mut self, interpreter: &mut dyn InterpreterTrait)
-        -> Result<LiteralValue, String>;
-    fn resolve(&mut self, resolver: &mut Resolver);
+        -> Result<LiteralValue, LoxError>;
+    fn resolve(&mut self, resolver: &mut Resolver) -> Result<(), LoxError>;
+    fn compile(&mut self, compiler: &mut Compiler);
     fn as_any(&self) -> &dyn Any;
     fn clone_box(&self) -> Box<dyn Expression>;
 }
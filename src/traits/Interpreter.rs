@@ -1,23 +1,37 @@
+use std::any::Any;
+
+use crate::enums::ControlFlow::ControlFlow;
 use crate::enums::LiteralValue::LiteralValue;
+use crate::enums::LoxError::LoxError;
 use crate::implementation::AssignmentExpression::AssignmentExpression;
 use crate::implementation::BinaryExpression::BinaryExpression;
 use crate::implementation::BlockStatement::BlockStatement;
+use crate::implementation::BreakStatement::BreakStatement;
 use crate::implementation::CallExpression::CallExpression;
 use crate::implementation::ClassStatement::ClassStatement;
+use crate::implementation::ContinueStatement::ContinueStatement;
 use crate::implementation::Environment::Environment;
 use crate::implementation::ExpressionStatement::ExpressionStatement;
+use crate::implementation::FunctionExpression::FunctionExpression;
 use crate::implementation::FunctionStatement::FunctionStatement;
 use crate::implementation::GetExpression::GetExpression;
 use crate::implementation::Grouping::Grouping;
 use crate::implementation::IfStatement::IfStatement;
+use crate::implementation::ImportStatement::ImportStatement;
+use crate::implementation::IndexExpression::IndexExpression;
+use crate::implementation::IndexSetExpression::IndexSetExpression;
+use crate::implementation::ListExpression::ListExpression;
 use crate::implementation::Literal::Literal;
 use crate::implementation::LogicalExpression::LogicalExpression;
+use crate::implementation::MapExpression::MapExpression;
 use crate::implementation::PrintStatement::PrintStatement;
 use crate::implementation::ReturnStatement::ReturnStatement;
 use crate::implementation::SetExpression::SetExpression;
 use crate::implementation::SuperExpression::SuperExpression;
 use crate::implementation::ThisExpression::ThisExpression;
+use crate::implementation::ThrowStatement::ThrowStatement;
 use crate::implementation::Token::Token;
+use crate::implementation::TryStatement::TryStatement;
 use crate::implementation::UnaryExpression::UnaryExpression;
 use crate::implementation::VariableExpression::VariableExpression;
 use crate::implementation::VariableStatement::VariableStatement;
@@ -27,105 +41,146 @@ use crate::traits::Expression::Expression;
 use super::Statement::Statement;
 
 pub trait InterpreterTrait {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
     fn define_globals(&mut self);
     fn resolve(&mut self, expression: &mut dyn Expression, depth: usize);
     fn look_up_variable(
         &mut self,
         token: &Token,
         expression: &dyn Expression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_class_statement(
         &mut self,
         statement: &mut ClassStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_binary_expression(
         &mut self,
         expression: &mut BinaryExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_unary_expression(
         &mut self,
         expression: &mut UnaryExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_logical_expression(
         &mut self,
         expression: &mut LogicalExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_print_statement(
         &mut self,
         statement: &mut PrintStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_expression_statement(
         &mut self,
         statement: &mut ExpressionStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_variable_statement(
         &mut self,
         statement: &mut VariableStatement,
-    ) -> Result<Option<LiteralValue>, String>;
-    fn visit_grouping(&mut self, expression: &mut Grouping) -> Result<LiteralValue, String>;
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_grouping(&mut self, expression: &mut Grouping) -> Result<LiteralValue, LoxError>;
     fn visit_super_expression(
         &mut self,
         expression: &mut SuperExpression,
-    ) -> Result<LiteralValue, String>;
-    fn visit_literal(&self, expression: &Literal) -> Result<LiteralValue, String>;
-    fn evaluate(&mut self, expression: &mut Box<dyn Expression>) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_literal(&self, expression: &Literal) -> Result<LiteralValue, LoxError>;
+    fn evaluate(&mut self, expression: &mut Box<dyn Expression>) -> Result<LiteralValue, LoxError>;
     fn is_truthy(&self, expression: &LiteralValue) -> bool;
     fn is_equal(&self, left: &LiteralValue, right: &LiteralValue) -> bool;
     fn execute(
         &mut self,
         statement: &mut Box<dyn Statement>,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn execute_block(
         &mut self,
         statement: &mut Vec<Box<dyn Statement>>,
-    ) -> Result<Option<LiteralValue>, String>;
-    fn error(&self, message: String, token: &Token) -> String;
+    ) -> Result<ControlFlow, LoxError>;
+    fn error(&self, message: String, token: &Token) -> LoxError;
     fn interpret(
         &mut self,
         statements: &mut Vec<Box<dyn Statement>>,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_variable_expression(
         &mut self,
         expression: &VariableExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_get_expression(
         &mut self,
         expression: &mut GetExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_set_expression(
         &mut self,
         expression: &mut SetExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_function_expression(
+        &mut self,
+        expression: &mut FunctionExpression,
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_list_expression(
+        &mut self,
+        expression: &mut ListExpression,
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_map_expression(
+        &mut self,
+        expression: &mut MapExpression,
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_index_expression(
+        &mut self,
+        expression: &mut IndexExpression,
+    ) -> Result<LiteralValue, LoxError>;
+    fn visit_index_set_expression(
+        &mut self,
+        expression: &mut IndexSetExpression,
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_this_expression(
         &mut self,
         expression: &mut ThisExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_call_expression(
         &mut self,
         expression: &mut CallExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_assignment_expression(
         &mut self,
         expression: &mut AssignmentExpression,
-    ) -> Result<LiteralValue, String>;
+    ) -> Result<LiteralValue, LoxError>;
     fn visit_block_statement(
         &mut self,
         statement: &mut BlockStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_if_statement(
         &mut self,
         statement: &mut IfStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_while_statement(
         &mut self,
         statement: &mut WhileStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_function_statement(
         &mut self,
         statement: &mut FunctionStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn visit_return_statement(
         &mut self,
         statement: &mut ReturnStatement,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_import_statement(
+        &mut self,
+        statement: &mut ImportStatement,
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_throw_statement(
+        &mut self,
+        statement: &mut ThrowStatement,
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_try_statement(
+        &mut self,
+        statement: &mut TryStatement,
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_break_statement(
+        &mut self,
+        statement: &mut BreakStatement,
+    ) -> Result<ControlFlow, LoxError>;
+    fn visit_continue_statement(
+        &mut self,
+        statement: &mut ContinueStatement,
+    ) -> Result<ControlFlow, LoxError>;
 }
@@ -1,10 +1,13 @@
 use crate::{
-    enums::LiteralValue::LiteralValue,
+    enums::{LiteralValue::LiteralValue, LoxError::LoxError},
     implementation::{Interpreter::Interpreter, LoxInstance::LoxInstance},
 };
 
 pub trait LoxCallableTrait {
     fn arity(&mut self) -> usize;
-    fn call(&mut self, interpreter: &mut Interpreter, arguments: Vec<LiteralValue>)
-        -> LiteralValue;
+    fn call(
+        &mut self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LiteralValue>,
+    ) -> Result<LiteralValue, LoxError>;
 }
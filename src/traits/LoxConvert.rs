@@ -0,0 +1,173 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::enums::LiteralValue::LiteralValue;
+
+/// Rust types a Lox argument can be converted into. `expected` names the
+/// accepted Lox type for the error raised when `from_lox` returns `None`.
+pub trait FromLox: Sized {
+    fn from_lox(value: &LiteralValue) -> Option<Self>;
+    fn expected() -> String;
+}
+
+/// Rust types that can be handed back to Lox.
+pub trait IntoLox {
+    fn into_lox(self) -> LiteralValue;
+}
+
+/// What a host function may return: a plain value, or a `Result` whose
+/// `Err` becomes a Lox runtime error.
+pub trait IntoNativeResult {
+    fn into_native_result(self) -> Result<LiteralValue, String>;
+}
+
+impl<T: IntoLox> IntoNativeResult for T {
+    fn into_native_result(self) -> Result<LiteralValue, String> {
+        Ok(self.into_lox())
+    }
+}
+
+impl<T: IntoLox> IntoNativeResult for Result<T, String> {
+    fn into_native_result(self) -> Result<LiteralValue, String> {
+        self.map(IntoLox::into_lox)
+    }
+}
+
+impl FromLox for LiteralValue {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        Some(value.clone())
+    }
+
+    fn expected() -> String {
+        String::from("any value")
+    }
+}
+
+impl FromLox for f64 {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        match value {
+            LiteralValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        String::from("number")
+    }
+}
+
+impl FromLox for i64 {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        match value {
+            LiteralValue::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(63) => {
+                Some(*n as i64)
+            }
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        String::from("integer")
+    }
+}
+
+impl FromLox for bool {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        match value {
+            LiteralValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        String::from("boolean")
+    }
+}
+
+impl FromLox for String {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        match value {
+            LiteralValue::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn expected() -> String {
+        String::from("string")
+    }
+}
+
+/// `nil` converts to `None`.
+impl<T: FromLox> FromLox for Option<T> {
+    fn from_lox(value: &LiteralValue) -> Option<Self> {
+        match value {
+            LiteralValue::Nil => Some(None),
+            other => T::from_lox(other).map(Some),
+        }
+    }
+
+    fn expected() -> String {
+        format!("{} or nil", T::expected())
+    }
+}
+
+impl IntoLox for LiteralValue {
+    fn into_lox(self) -> LiteralValue {
+        self
+    }
+}
+
+impl IntoLox for () {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::Nil
+    }
+}
+
+impl IntoLox for f64 {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::Number(self)
+    }
+}
+
+impl IntoLox for i64 {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::Number(self as f64)
+    }
+}
+
+impl IntoLox for usize {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::Number(self as f64)
+    }
+}
+
+impl IntoLox for bool {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::Boolean(self)
+    }
+}
+
+impl IntoLox for String {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::String(self)
+    }
+}
+
+impl IntoLox for &str {
+    fn into_lox(self) -> LiteralValue {
+        LiteralValue::String(self.to_string())
+    }
+}
+
+impl<T: IntoLox> IntoLox for Option<T> {
+    fn into_lox(self) -> LiteralValue {
+        self.map_or(LiteralValue::Nil, IntoLox::into_lox)
+    }
+}
+
+/// Becomes a new Lox list.
+impl<T: IntoLox> IntoLox for Vec<T> {
+    fn into_lox(self) -> LiteralValue {
+        let elements = self.into_iter().map(IntoLox::into_lox).collect();
+        LiteralValue::List(Rc::new(RefCell::new(elements)))
+    }
+}
@@ -1,6 +1,9 @@
 use std::any::Any;
 
-use crate::{enums::LiteralValue::LiteralValue, implementation::Resolver::Resolver};
+use crate::{
+    compiler::Compiler::Compiler, enums::{ControlFlow::ControlFlow, LoxError::LoxError},
+    implementation::Resolver::Resolver,
+};
 
 use super::Interpreter::InterpreterTrait;
 
@@ -8,8 +11,9 @@ pub trait Statement {
     fn interpret(
         &mut self,
         interpreter: &mut dyn InterpreterTrait,
-    ) -> Result<Option<LiteralValue>, String>;
+    ) -> Result<ControlFlow, LoxError>;
     fn clone_box(&self) -> Box<dyn Statement>;
-    fn resolve(&mut self, resolver: &mut Resolver);
+    fn resolve(&mut self, resolver: &mut Resolver) -> Result<(), LoxError>;
+    fn compile(&mut self, compiler: &mut Compiler);
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -2,4 +2,5 @@ pub mod AstPrinter;
 pub mod Expression;
 pub mod Interpreter;
 pub mod Statement;
-pub mod LoxCallableTrait;
\ No newline at end of file
+pub mod LoxCallableTrait;
+pub mod LoxConvert;
\ No newline at end of file
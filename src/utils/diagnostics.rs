@@ -0,0 +1,132 @@
+use std::io::{self, IsTerminal};
+
+use crate::{enums::LoxError::LoxError, implementation::Token::Span};
+
+/// How errors are written to stderr.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    /// The original `[line N] Error at 'x': ...` text the test suite expects,
+    /// followed by the traceback of runtime errors raised inside functions.
+    /// Selected with `--error-format=plain`.
+    PLAIN,
+    /// A rustc-style report with the offending source line and a caret,
+    /// selected with `--error-format=rich`.
+    RICH,
+}
+
+impl Default for ErrorFormat {
+    /// Rich when stderr is a terminal, where a person reads it. Plain when
+    /// it is piped or redirected, so test harnesses and scripts that match
+    /// on the `[line N] Error` text keep working without a flag.
+    fn default() -> ErrorFormat {
+        match io::stderr().is_terminal() {
+            true => ErrorFormat::RICH,
+            false => ErrorFormat::PLAIN,
+        }
+    }
+}
+
+impl ErrorFormat {
+    /// Parses the value of `--error-format=`.
+    pub fn from_flag(value: &str) -> Option<ErrorFormat> {
+        match value {
+            "plain" => Some(ErrorFormat::PLAIN),
+            "rich" => Some(ErrorFormat::RICH),
+            _ => None,
+        }
+    }
+}
+
+pub fn format_error(error: &LoxError, format: ErrorFormat, source: &str, path: &str) -> String {
+    match format {
+        ErrorFormat::PLAIN => {
+            let mut text = error.to_string();
+            for frame in error.traceback() {
+                text.push('\n');
+                text.push_str(&frame);
+            }
+            text
+        }
+        ErrorFormat::RICH => render(error, source, path),
+    }
+}
+
+/// Renders `error` as
+///
+/// ```text
+/// error: Expect expression.
+///  --> main.lox:3:9
+///   |
+/// 3 | var a = ;
+///   |         ^
+///   = note: ...
+/// ```
+///
+/// Errors without a span (from the bytecode VM) still show the whole line.
+fn render(error: &LoxError, source: &str, path: &str) -> String {
+    let (line, column, width) = error
+        .span()
+        .and_then(|span| locate(source, span))
+        .unwrap_or((error.line() as usize, 0, 0));
+    let text = source.lines().nth(line.saturating_sub(1));
+
+    let gutter = " ".repeat(line.to_string().len());
+    let mut out = format!("error: {}\n", error.message());
+    match column {
+        0 => out.push_str(&format!("{}--> {}:{}\n", gutter, path, line)),
+        _ => out.push_str(&format!("{}--> {}:{}:{}\n", gutter, path, line, column)),
+    }
+    if let Some(text) = text {
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", line, text));
+        if column > 0 {
+            // Reuse the line's own whitespace so tabs keep the caret aligned.
+            let padding: String = text
+                .chars()
+                .take(column - 1)
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+            out.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(width)));
+        }
+    }
+    if let Some(note) = error.note() {
+        out.push_str(&format!("{} = note: {}\n", gutter, note));
+    }
+    let traceback = error.traceback();
+    if !traceback.is_empty() {
+        out.push_str("stack traceback:\n");
+        for frame in traceback {
+            out.push_str(&format!("  {}\n", frame));
+        }
+    }
+    out.pop();
+    out
+}
+
+/// Line, column and caret width for `span`, all measured in characters.
+/// Spans that run past the end of their line are cut off at the newline,
+/// and an empty span at the very end of the file points just past the
+/// last character rather than at an empty final line. `None` if the span
+/// doesn't fit `source`, which means it was taken from some other text.
+fn locate(source: &str, span: Span) -> Option<(usize, usize, usize)> {
+    if span.start > span.end
+        || span.end > source.len()
+        || !source.is_char_boundary(span.start)
+        || !source.is_char_boundary(span.end)
+    {
+        return None;
+    }
+    let mut start = span.start;
+    if start == source.len() && source.ends_with('\n') {
+        start = source.trim_end_matches('\n').len();
+    }
+    let line = source[..start].matches('\n').count() + 1;
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let column = source[line_start..start].chars().count() + 1;
+    let end = span.end.max(start);
+    let width = source[start..end]
+        .split('\n')
+        .next()
+        .map_or(0, |token| token.chars().count());
+    Some((line, column, width.max(1)))
+}
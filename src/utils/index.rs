@@ -1,13 +1,16 @@
 use crate::constants::constants::RESERVED_KEYWORDS_MAP;
-use crate::implementation::Token::Token;
+use crate::enums::LoxError::LoxError;
+use crate::implementation::Token::{Span, Token};
 
 use std::io::{self, Write};
-use std::iter::Peekable;
 use std::str::Chars;
 
-use anyhow::{anyhow, Error, Result};
+/// The next character without consuming it.
+pub fn peek(chars: &Chars) -> Option<char> {
+    chars.clone().next()
+}
 
-pub fn consume_until_next_line(chars: &mut Peekable<Chars>) {
+pub fn consume_until_next_line(chars: &mut Chars) {
     while let Some(char) = chars.next() {
         if char == '\n' {
             break;
@@ -15,29 +18,144 @@ pub fn consume_until_next_line(chars: &mut Peekable<Chars>) {
     }
 }
 
-pub fn consume_until_next_double_quote(chars: &mut Peekable<Chars>) -> Result<String, Error> {
+/// Skips a `/* ... */` comment, which may contain nested block comments,
+/// bumping `line` for every newline inside it. `chars` must sit just past
+/// the opening `/*`.
+pub fn consume_block_comment(
+    source: &str,
+    chars: &mut Chars,
+    line: &mut u32,
+) -> Result<(), LoxError> {
+    let start = source.len() - chars.as_str().len() - 2;
+    let start_line = *line;
+    let mut depth = 1;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '/' if peek(chars) == Some('*') => {
+                chars.next();
+                depth += 1;
+            }
+            '*' if peek(chars) == Some('/') => {
+                chars.next();
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            }
+            '\n' => *line += 1,
+            _ => {}
+        }
+    }
+    Err(LoxError::lex(
+        start_line,
+        Span::new(source, start, source.len()),
+        String::from("Unterminated comment."),
+    ))
+}
+
+/// Reads a string literal up to its closing quote, decoding escape sequences
+/// and bumping `line` for every newline inside it. `chars` must sit just
+/// past the opening quote. After a bad escape the rest of the literal is
+/// still consumed so lexing resumes after the closing quote.
+pub fn consume_until_next_double_quote(
+    source: &str,
+    chars: &mut Chars,
+    line: &mut u32,
+) -> Result<String, LoxError> {
+    let start = source.len() - chars.as_str().len() - 1;
+    let start_line = *line;
     let mut literal = String::new();
+    let mut error = None;
 
     while let Some(ch) = chars.next() {
-        if ch == '"' {
-            return Ok(literal);
-        } else {
-            literal.push(ch);
+        match ch {
+            '"' => return error.map_or(Ok(literal), Err),
+            '\\' => {
+                let escape_start = source.len() - chars.as_str().len() - 1;
+                match read_escape(chars) {
+                    Ok(decoded) => literal.push(decoded),
+                    Err(message) => {
+                        let escape_end = source.len() - chars.as_str().len();
+                        let span = Span::new(source, escape_start, escape_end);
+                        error.get_or_insert(LoxError::lex(*line, span, message));
+                    }
+                }
+            }
+            '\n' => {
+                *line += 1;
+                literal.push(ch);
+            }
+            _ => literal.push(ch),
         }
     }
-    Err(anyhow!("Unterminated string literal"))
+    Err(LoxError::lex(
+        start_line,
+        Span::new(source, start, source.len()),
+        String::from("Unterminated string."),
+    ))
+}
+
+/// Decodes the escape sequence after a backslash. A newline is left
+/// unconsumed so the caller still counts it.
+fn read_escape(chars: &mut Chars) -> Result<char, String> {
+    let decoded = match peek(chars) {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('"') => '"',
+        Some('\\') => '\\',
+        Some('u') => {
+            chars.next();
+            return read_unicode_escape(chars);
+        }
+        Some('\n') | None => return Err(String::from("Unfinished escape sequence.")),
+        Some(other) => {
+            chars.next();
+            return Err(format!("Unknown escape sequence '\\{}'.", other));
+        }
+    };
+    chars.next();
+    Ok(decoded)
+}
+
+/// Decodes the `{XXXX}` part of a `\u{XXXX}` escape: one to six hex digits
+/// naming a Unicode scalar value.
+fn read_unicode_escape(chars: &mut Chars) -> Result<char, String> {
+    let invalid =
+        || String::from("Invalid unicode escape; expected \\u{...} with 1 to 6 hex digits.");
+    if peek(chars) != Some('{') {
+        return Err(invalid());
+    }
+    chars.next();
+    let mut digits = String::new();
+    while let Some(ch) = peek(chars) {
+        if !ch.is_ascii_hexdigit() {
+            break;
+        }
+        digits.push(ch);
+        chars.next();
+    }
+    if peek(chars) != Some('}') || digits.is_empty() || digits.len() > 6 {
+        return Err(invalid());
+    }
+    chars.next();
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("'{}' is not a valid unicode code point.", digits))
 }
 
-pub fn get_number(chars: &mut Peekable<Chars>) -> String {
+pub fn get_number(chars: &mut Chars) -> String {
     let mut number = String::new();
     let mut dot_used = false;
-    while let Some(ch) = chars.peek() {
+    while let Some(ch) = peek(chars) {
         if ch.is_digit(10) {
-            number.push(*ch);
+            number.push(ch);
             chars.next();
-        } else if *ch == '.' && !dot_used {
+        } else if ch == '.' && !dot_used {
             dot_used = true;
-            number.push(*ch);
+            number.push(ch);
             chars.next();
         } else {
             break;
@@ -46,11 +164,11 @@ pub fn get_number(chars: &mut Peekable<Chars>) -> String {
     return number;
 }
 
-pub fn get_identifier(chars: &mut Peekable<Chars>) -> String {
+pub fn get_identifier(chars: &mut Chars) -> String {
     let mut identifier = String::new();
-    while let Some(ch) = chars.peek() {
-        if ch.is_alphabetic() || *ch == '_' || ch.is_digit(10) {
-            identifier.push(*ch);
+    while let Some(ch) = peek(chars) {
+        if ch.is_alphabetic() || ch == '_' || ch.is_digit(10) {
+            identifier.push(ch);
             chars.next();
         } else {
             break;
@@ -59,12 +177,14 @@ pub fn get_identifier(chars: &mut Peekable<Chars>) -> String {
     return identifier;
 }
 
-pub fn get_if_reserved_keyword(chars: &mut Peekable<Chars>, line: u32) -> Option<Token> {
+pub fn get_if_reserved_keyword(chars: &mut Chars, line: u32) -> Option<Token> {
     let mut word = String::new();
     let mut cloned_chars = chars.clone();
 
-    while let Some(&ch) = cloned_chars.peek() {
-        if !ch.is_alphabetic() {
+    // Read the whole word, as `get_identifier` would, so `try2` or
+    // `import_x` isn't taken for the keyword it starts with.
+    while let Some(ch) = peek(&cloned_chars) {
+        if !(ch.is_alphabetic() || ch == '_' || ch.is_digit(10)) {
             break;
         }
         word.push(ch);
@@ -76,6 +196,7 @@ pub fn get_if_reserved_keyword(chars: &mut Peekable<Chars>, line: u32) -> Option
             token_type: token,
             token_value: word,
             line,
+            span: Span::default(),
         });
     }
     return None;
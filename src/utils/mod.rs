@@ -1 +1,4 @@
+pub mod diagnostics;
 pub mod index;
+pub mod repl;
+pub mod tokenizer;
@@ -0,0 +1,119 @@
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::{enums::LoxError::LoxError, implementation::Lox::Lox};
+
+use super::diagnostics::{format_error, ErrorFormat};
+
+const HISTORY_FILE: &str = ".lox_history";
+
+/// Net count of `{` over `}` in `source`, ignoring string literals and
+/// `//` comments. Positive means the input is still inside a block.
+fn open_braces(source: &str) -> i32 {
+    let mut depth = 0;
+    let mut chars = source.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut comments = 1;
+                while comments > 0 {
+                    match chars.next() {
+                        Some('/') if chars.peek() == Some(&'*') => {
+                            chars.next();
+                            comments += 1;
+                        }
+                        Some('*') if chars.peek() == Some(&'/') => {
+                            chars.next();
+                            comments -= 1;
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Writes `errors` in the rich format, each against the input or module it
+/// was raised in.
+fn report(lox: &mut Lox, errors: &[LoxError], source: &str) {
+    for error in errors {
+        let text = match lox.source_of(error) {
+            Some((path, module)) => format_error(error, ErrorFormat::RICH, &module, &path),
+            None => format_error(error, ErrorFormat::RICH, source, "<repl>"),
+        };
+        eprintln!("{}", text);
+    }
+}
+
+/// Reads lines from the terminal and runs them in a single [`Lox`] session
+/// until end of input. Errors are reported and the session carries on.
+pub fn run_repl() -> rustyline::Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    let history = std::env::var("HOME")
+        .map(|home| std::path::Path::new(&home).join(HISTORY_FILE))
+        .ok();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    let mut lox = Lox::new();
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() { "> " } else { "... " };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                buffer.push_str(&line);
+                buffer.push('\n');
+                if open_braces(&buffer) > 0 {
+                    continue;
+                }
+                let source = std::mem::take(&mut buffer);
+                if source.trim().is_empty() {
+                    continue;
+                }
+                let _ = editor.add_history_entry(source.trim_end());
+                let echo = lox
+                    .run_interactive(&source)
+                    .and_then(|value| value.map(|value| lox.stringify(&value)).transpose());
+                match echo {
+                    Ok(Some(text)) => println!("{}", text),
+                    Ok(None) => {}
+                    Err(errors) => report(&mut lox, &errors, &source),
+                }
+            }
+            // Ctrl-C abandons the current (possibly multi-line) input.
+            Err(ReadlineError::Interrupted) => buffer.clear(),
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+    Ok(())
+}
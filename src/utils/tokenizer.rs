@@ -0,0 +1,329 @@
+use crate::enums::{LoxError::LoxError, TokenType::TokenType};
+use crate::implementation::Token::{Span, Token};
+
+use super::index::{
+    consume_block_comment, consume_until_next_double_quote, consume_until_next_line,
+    get_identifier, get_if_reserved_keyword, get_number, peek,
+};
+
+pub struct TokenizeResult {
+    pub tokens: Vec<Token>,
+    pub errors: Vec<LoxError>,
+}
+
+impl TokenizeResult {
+    /// Tags every token and lex error span with `file`, for source that is
+    /// not the main script.
+    pub fn in_file(mut self, file: u32) -> Self {
+        for token in &mut self.tokens {
+            token.span.file = file;
+        }
+        for error in &mut self.errors {
+            if let LoxError::Lex { span, .. } = error {
+                span.file = file;
+            }
+        }
+        self
+    }
+}
+
+pub fn tokenize(file_contents: String) -> TokenizeResult {
+    let mut chars = file_contents.chars();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut errors = Vec::new();
+    let mut line = 1;
+
+    while let Some(ch) = peek(&chars) {
+        let start = file_contents.len() - chars.as_str().len();
+        let token_count = tokens.len();
+        match ch {
+            '(' => {
+                tokens.push(Token {
+                    token_type: TokenType::LEFT_PAREN,
+                    token_value: "(".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token {
+                    token_type: TokenType::RIGHT_PAREN,
+                    token_value: ")".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '{' => {
+                tokens.push(Token {
+                    token_type: TokenType::LEFT_BRACE,
+                    token_value: "{".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '}' => {
+                tokens.push(Token {
+                    token_type: TokenType::RIGHT_BRACE,
+                    token_value: "}".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '[' => {
+                tokens.push(Token {
+                    token_type: TokenType::LEFT_BRACKET,
+                    token_value: "[".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            ']' => {
+                tokens.push(Token {
+                    token_type: TokenType::RIGHT_BRACKET,
+                    token_value: "]".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '*' => {
+                tokens.push(Token {
+                    token_type: TokenType::STAR,
+                    token_value: "*".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '.' => {
+                tokens.push(Token {
+                    token_type: TokenType::DOT,
+                    token_value: ".".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            ':' => {
+                tokens.push(Token {
+                    token_type: TokenType::COLON,
+                    token_value: ":".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            ',' => {
+                tokens.push(Token {
+                    token_type: TokenType::COMMA,
+                    token_value: ",".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '+' => {
+                tokens.push(Token {
+                    token_type: TokenType::PLUS,
+                    token_value: "+".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '-' => {
+                tokens.push(Token {
+                    token_type: TokenType::MINUS,
+                    token_value: "-".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            ';' => {
+                tokens.push(Token {
+                    token_type: TokenType::SEMICOLON,
+                    token_value: ";".to_string(),
+                    line,
+                    span: Span::default(),
+                });
+                chars.next();
+            }
+            '=' => {
+                chars.next();
+                let token = if peek(&chars) == Some('=') {
+                    chars.next();
+                    Token {
+                        token_type: TokenType::EQUAL_EQUAL,
+                        token_value: "==".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::EQUAL,
+                        token_value: "=".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                };
+                tokens.push(token);
+            }
+            '!' => {
+                chars.next();
+                let token = if peek(&chars) == Some('=') {
+                    chars.next();
+                    Token {
+                        token_type: TokenType::BANG_EQUAL,
+                        token_value: "!=".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::BANG,
+                        token_value: "!".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                };
+                tokens.push(token);
+            }
+            '<' => {
+                chars.next();
+                let token = if peek(&chars) == Some('=') {
+                    chars.next();
+                    Token {
+                        token_type: TokenType::LESS_EQUAL,
+                        token_value: "<=".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::LESS,
+                        token_value: "<".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                };
+                tokens.push(token);
+            }
+            '>' => {
+                chars.next();
+                let token = if peek(&chars) == Some('=') {
+                    chars.next();
+                    Token {
+                        token_type: TokenType::GREATER_EQUAL,
+                        token_value: ">=".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::GREATER,
+                        token_value: ">".to_string(),
+                        line,
+                        span: Span::default(),
+                    }
+                };
+                tokens.push(token);
+            }
+            '/' => {
+                chars.next();
+                if peek(&chars) == Some('/') {
+                    chars.next(); // Consume second '/'
+                    consume_until_next_line(&mut chars);
+                    line += 1;
+                } else if peek(&chars) == Some('*') {
+                    chars.next(); // Consume the '*'
+                    if let Err(error) = consume_block_comment(&file_contents, &mut chars, &mut line)
+                    {
+                        errors.push(error);
+                    }
+                } else {
+                    tokens.push(Token {
+                        token_type: TokenType::SLASH,
+                        token_value: "/".to_string(),
+                        line,
+                        span: Span::default(),
+                    });
+                }
+            }
+            '"' => {
+                chars.next(); // Consume the opening quote.
+                let start_line = line;
+                match consume_until_next_double_quote(&file_contents, &mut chars, &mut line) {
+                    Ok(literal) => {
+                        tokens.push(Token {
+                            token_type: TokenType::STRING,
+                            token_value: literal,
+                            line: start_line,
+                            span: Span::default(),
+                        });
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+            '\n' => {
+                chars.next();
+                line += 1;
+            }
+            '\t' | ' ' => {
+                chars.next();
+            }
+            fallback => {
+                if fallback.is_digit(10) {
+                    let number_str = get_number(&mut chars);
+                    tokens.push(Token {
+                        token_type: TokenType::NUMBER,
+                        token_value: number_str,
+                        line,
+                        span: Span::default(),
+                    });
+                } else if fallback.is_alphabetic() || fallback == '_' {
+                    if let Some(result) = get_if_reserved_keyword(&mut chars, line) {
+                        for _ in 0..result.token_value.len() {
+                            chars.next();
+                        }
+                        tokens.push(result);
+                    } else {
+                        let identifier = get_identifier(&mut chars);
+                        tokens.push(Token {
+                            token_type: TokenType::IDENTIFIER,
+                            token_value: identifier,
+                            line,
+                            span: Span::default(),
+                        });
+                    }
+                } else {
+                    chars.next();
+                    let end = file_contents.len() - chars.as_str().len();
+                    errors.push(LoxError::lex(
+                        line,
+                        Span::new(&file_contents, start, end),
+                        format!("Unexpected character: {}", fallback),
+                    ));
+                }
+            }
+        }
+        // Every branch builds its token with a placeholder span; fill it in
+        // now that we know how much input the token consumed.
+        if tokens.len() > token_count {
+            let end = file_contents.len() - chars.as_str().len();
+            tokens.last_mut().unwrap().span = Span::new(&file_contents, start, end);
+        }
+    }
+    tokens.push(Token {
+        token_type: TokenType::EOF,
+        token_value: "EOF".to_string(),
+        line,
+        span: Span::new(&file_contents, file_contents.len(), file_contents.len()),
+    });
+    TokenizeResult { tokens, errors }
+}
@@ -0,0 +1,115 @@
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, rc::Rc};
+
+use indexmap::IndexMap;
+
+use crate::{compiler::Chunk::Function, enums::MapKey::MapKey};
+
+use super::{Value::Value, Vm::Vm};
+
+pub enum Upvalue {
+    /// Still lives on the VM stack at this slot.
+    Open(usize),
+    /// Hoisted off the stack once its scope ended.
+    Closed(Value),
+}
+
+/// The global variables of the main script or of one module.
+pub type Globals = Rc<RefCell<HashMap<Rc<str>, Value>>>;
+
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    /// The globals of the script or module the closure was created in,
+    /// which its global variable instructions read and write.
+    pub globals: Globals,
+}
+
+/// An imported file, as bound by one `import`. Mirrors `LoxModule`: every
+/// import of the same file shares its `globals` but keeps its own `name`.
+pub struct Module {
+    pub name: String,
+    pub path: PathBuf,
+    pub globals: Globals,
+}
+
+/// A native receives the VM so it can call back into Lox code. An `Err`
+/// becomes a runtime error at the call site.
+#[derive(Clone)]
+pub struct NativeFunction {
+    pub arity: usize,
+    pub function: fn(&mut Vm, Vec<Value>) -> Result<Value, String>,
+}
+
+/// Instance methods and setters are copied down from the superclass on
+/// `INHERIT`; static
+/// members are looked up through `superclass` so later assignments to a
+/// superclass's static fields stay visible.
+pub struct Class {
+    pub name: String,
+    pub methods: HashMap<String, Rc<Closure>>,
+    pub static_methods: HashMap<String, Rc<Closure>>,
+    pub setters: HashMap<String, Rc<Closure>>,
+    pub fields: HashMap<String, Value>,
+    pub superclass: Option<Rc<RefCell<Class>>>,
+}
+
+impl Class {
+    pub fn find_static_method(&self, name: &str) -> Option<Rc<Closure>> {
+        if let Some(method) = self.static_methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+        self.superclass.as_ref()?.borrow().find_static_method(name)
+    }
+
+    pub fn find_field(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.fields.get(name) {
+            return Some(value.clone());
+        }
+        self.superclass.as_ref()?.borrow().find_field(name)
+    }
+}
+
+pub struct Instance {
+    pub klass: Rc<RefCell<Class>>,
+    pub fields: HashMap<String, Value>,
+}
+
+pub type ListRef = Rc<RefCell<Vec<Value>>>;
+
+/// A native list method bound to its list, e.g. the value of `xs.push`.
+pub struct ListMethod {
+    pub list: ListRef,
+    pub name: Rc<str>,
+}
+
+impl ListMethod {
+    pub fn arity(&self) -> usize {
+        match &*self.name {
+            "push" | "remove" => 1,
+            "insert" => 2,
+            _ => 0,
+        }
+    }
+}
+
+pub type MapRef = Rc<RefCell<IndexMap<MapKey, Value>>>;
+
+/// A native map method bound to its map, e.g. the value of `m.keys`.
+pub struct MapMethod {
+    pub map: MapRef,
+    pub name: Rc<str>,
+}
+
+impl MapMethod {
+    pub fn arity(&self) -> usize {
+        match &*self.name {
+            "has" | "remove" => 1,
+            _ => 0,
+        }
+    }
+}
+
+pub struct BoundMethod {
+    pub receiver: Value,
+    pub method: Rc<Closure>,
+}
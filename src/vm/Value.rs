@@ -0,0 +1,269 @@
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use crate::{
+    enums::MapKey::{MapKey, ObjectKey},
+    implementation::Token::Span,
+};
+
+use super::Object::{
+    BoundMethod, Class, Closure, Instance, ListMethod, ListRef, MapMethod, MapRef, Module,
+    NativeFunction,
+};
+
+/// Gives the text of an instance inside a printed list or map.
+pub type InstanceText<'a, E> = dyn FnMut(&Value) -> Result<String, E> + 'a;
+
+/// Runtime values of the bytecode VM. Mirrors `LiteralValue` for the tree-walker.
+#[derive(Clone, Default)]
+pub enum Value {
+    #[default]
+    Nil,
+    Boolean(bool),
+    Number(f64),
+    String(Rc<str>),
+    Closure(Rc<Closure>),
+    Native(NativeFunction),
+    Class(Rc<RefCell<Class>>),
+    Instance(Rc<RefCell<Instance>>),
+    BoundMethod(Rc<BoundMethod>),
+    List(ListRef),
+    ListMethod(Rc<ListMethod>),
+    Map(MapRef),
+    MapMethod(Rc<MapMethod>),
+    Module(Rc<Module>),
+}
+
+impl Value {
+    /// Mirrors `MapKey::from_literal`.
+    pub fn to_map_key(&self) -> Option<MapKey> {
+        let key = |address: *const ()| {
+            ObjectKey::new(
+                self.clone(),
+                address as usize,
+                Span::default(),
+                self.to_string(),
+            )
+        };
+        match self {
+            Value::Nil => Some(MapKey::Nil),
+            Value::Boolean(val) => Some(MapKey::Boolean(*val)),
+            Value::Number(num) => Some(MapKey::from_number(*num)),
+            Value::String(s) => Some(MapKey::String(s.to_string())),
+            Value::Instance(instance) => Some(MapKey::Instance(ObjectKey::instance(
+                instance,
+                self.to_string(),
+            ))),
+            Value::Class(class) => Some(MapKey::Class(key(Rc::as_ptr(class) as *const ()))),
+            Value::Closure(closure) => {
+                Some(MapKey::Function(key(Rc::as_ptr(closure) as *const ())))
+            }
+            Value::BoundMethod(bound) => {
+                Some(MapKey::Function(key(Rc::as_ptr(bound) as *const ())))
+            }
+            Value::Native(native) => Some(MapKey::Function(key(native.function as *const ()))),
+            _ => None,
+        }
+    }
+
+    pub fn from_map_key(key: &MapKey) -> Value {
+        match key {
+            MapKey::Nil => Value::Nil,
+            MapKey::Boolean(val) => Value::Boolean(*val),
+            MapKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            MapKey::String(s) => Value::String(Rc::from(s.as_str())),
+            MapKey::Instance(key) => {
+                Value::Instance(key.value().expect("VM maps only hold VM instances"))
+            }
+            MapKey::Class(key) | MapKey::Function(key) => {
+                key.value().expect("VM maps only hold VM values")
+            }
+        }
+    }
+
+    /// Mirrors `LiteralValue::fmt_collection`.
+    pub fn fmt_collection<E>(&self, instance: &mut InstanceText<E>) -> Result<String, E> {
+        let mut out = String::new();
+        match self {
+            Value::List(list) => Value::fmt_list(list, &mut out, &mut Vec::new(), instance)?,
+            Value::Map(map) => Value::fmt_map(map, &mut out, &mut Vec::new(), instance)?,
+            other => out.push_str(&other.to_string()),
+        }
+        Ok(out)
+    }
+
+    /// Mirrors `LiteralValue::fmt_element`: numbers as `print` shows them,
+    /// strings quoted, and self-containing collections cut short.
+    fn fmt_element<E>(
+        &self,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        match self {
+            Value::Number(num) => out.push_str(&num.to_string()),
+            Value::String(s) => out.push_str(&format!("\"{}\"", s)),
+            Value::List(list) => Value::fmt_list(list, out, seen, instance)?,
+            Value::Map(map) => Value::fmt_map(map, out, seen, instance)?,
+            Value::Instance(_) => out.push_str(&instance(self)?),
+            other => out.push_str(&other.to_string()),
+        }
+        Ok(())
+    }
+
+    fn fmt_list<E>(
+        list: &ListRef,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        let ptr = Rc::as_ptr(list) as *const ();
+        if seen.contains(&ptr) {
+            out.push_str("[...]");
+            return Ok(());
+        }
+        seen.push(ptr);
+        out.push('[');
+        let elements = list.borrow().clone();
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            element.fmt_element(out, seen, instance)?;
+        }
+        seen.pop();
+        out.push(']');
+        Ok(())
+    }
+
+    fn fmt_map<E>(
+        map: &MapRef,
+        out: &mut String,
+        seen: &mut Vec<*const ()>,
+        instance: &mut InstanceText<E>,
+    ) -> Result<(), E> {
+        let ptr = Rc::as_ptr(map) as *const ();
+        if seen.contains(&ptr) {
+            out.push_str("{...}");
+            return Ok(());
+        }
+        seen.push(ptr);
+        out.push('{');
+        let entries = map.borrow().clone();
+        for (i, (key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            match key {
+                MapKey::Instance(_) => Value::from_map_key(key).fmt_element(out, seen, instance)?,
+                key => out.push_str(&key.to_string()),
+            }
+            out.push_str(": ");
+            value.fmt_element(out, seen, instance)?;
+        }
+        seen.pop();
+        out.push('}');
+        Ok(())
+    }
+}
+
+/// Mirrors `LiteralValue`'s structural equality.
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        self.equals(other, &mut Vec::new())
+    }
+}
+
+impl Value {
+    /// Mirrors `LiteralValue::equals`, which guards against cycles.
+    fn equals(&self, other: &Value, seen: &mut Vec<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Number(left), Value::Number(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::List(left), Value::List(right)) => {
+                Value::collections_equal(left, right, seen, |left, right, seen| {
+                    let (left, right) = (left.borrow(), right.borrow());
+                    left.len() == right.len()
+                        && left
+                            .iter()
+                            .zip(right.iter())
+                            .all(|(l, r)| l.equals(r, seen))
+                })
+            }
+            (Value::Map(left), Value::Map(right)) => {
+                Value::collections_equal(left, right, seen, |left, right, seen| {
+                    let (left, right) = (left.borrow(), right.borrow());
+                    left.len() == right.len()
+                        && left
+                            .iter()
+                            .all(|(key, l)| right.get(key).is_some_and(|r| l.equals(r, seen)))
+                })
+            }
+            (Value::Instance(left), Value::Instance(right)) => Rc::ptr_eq(left, right),
+            (Value::Class(left), Value::Class(right)) => Rc::ptr_eq(left, right),
+            (Value::Closure(left), Value::Closure(right)) => Rc::ptr_eq(left, right),
+            (Value::BoundMethod(left), Value::BoundMethod(right)) => Rc::ptr_eq(left, right),
+            (Value::Module(left), Value::Module(right)) => {
+                Rc::ptr_eq(&left.globals, &right.globals)
+            }
+            (Value::Native(left), Value::Native(right)) => {
+                left.function as usize == right.function as usize
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs `contents_equal` on two lists or two maps unless they are the
+    /// same collection or a pair already being compared.
+    fn collections_equal<C>(
+        left: &Rc<C>,
+        right: &Rc<C>,
+        seen: &mut Vec<(*const (), *const ())>,
+        contents_equal: impl FnOnce(&Rc<C>, &Rc<C>, &mut Vec<(*const (), *const ())>) -> bool,
+    ) -> bool {
+        let pair = (
+            Rc::as_ptr(left) as *const (),
+            Rc::as_ptr(right) as *const (),
+        );
+        if Rc::ptr_eq(left, right) || seen.contains(&pair) {
+            return true;
+        }
+        seen.push(pair);
+        let equal = contents_equal(left, right, seen);
+        seen.pop();
+        equal
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Boolean(val) => write!(f, "{}", val),
+            Value::Number(num) => {
+                let formatted = format!("{}", num);
+                if formatted.contains('.') || !num.is_finite() {
+                    write!(f, "{}", formatted)
+                } else {
+                    write!(f, "{}.0", formatted)
+                }
+            }
+            Value::String(s) => write!(f, "{}", s),
+            Value::Closure(closure) => write!(f, "<fn {}>", closure.function.name),
+            Value::Native(_) | Value::ListMethod(_) | Value::MapMethod(_) => {
+                write!(f, "<native fn>")
+            }
+            Value::List(_) | Value::Map(_) => {
+                let text = self.fmt_collection(&mut |instance| Ok(instance.to_string()))?;
+                write!(f, "{}", text)
+            }
+            Value::Class(klass) => write!(f, "{}", klass.borrow().name),
+            Value::Instance(instance) => {
+                write!(f, "{} instance", instance.borrow().klass.borrow().name)
+            }
+            Value::BoundMethod(bound) => write!(f, "<fn {}>", bound.method.function.name),
+            Value::Module(module) => write!(f, "<module {}>", module.name),
+        }
+    }
+}
@@ -0,0 +1,1193 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::Path,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use indexmap::IndexMap;
+
+use crate::{
+    compiler::{
+        Chunk::{Constant, Function},
+        Compiler::{Compiler, SCRIPT},
+        OpCode::OpCode,
+    },
+    constants::constants::{operator_method, MAX_CALL_DEPTH},
+    enums::{LoxError::LoxError, MapKey::MapKey},
+    implementation::Module::{Import, ModuleRegistry},
+};
+
+use super::{
+    Object::{
+        BoundMethod, Class, Closure, Globals, Instance, ListMethod, MapMethod, Module,
+        NativeFunction, Upvalue,
+    },
+    Value::Value,
+};
+
+/// The calls `MAX_CALL_DEPTH` allows plus the script's own frame.
+const FRAMES_MAX: usize = MAX_CALL_DEPTH + 1;
+
+pub struct CallFrame {
+    pub closure: Rc<Closure>,
+    pub ip: usize,
+    /// Index of the frame's slot zero on the value stack.
+    pub slot_base: usize,
+    /// Set for setter calls: the assignment's value already sits below the
+    /// frame, so whatever the setter returns is dropped.
+    pub discard_result: bool,
+}
+
+/// Where a runtime error unwinds to, installed by `TRY` or `TRY_FINALLY`.
+pub struct Handler {
+    /// The frame count and stack height to unwind to.
+    pub frames: usize,
+    pub stack_height: usize,
+    /// How many errors were set aside when the handler was installed. Ones
+    /// set aside since then belong to `finally` blocks the error abandons.
+    pub pending: usize,
+    /// Where the handler's code starts in its frame's chunk.
+    pub ip: usize,
+    /// Set for `TRY_FINALLY`, whose code runs with the error set aside
+    /// instead of caught.
+    pub finally: bool,
+}
+
+#[derive(Default)]
+pub struct Vm {
+    pub stack: Vec<Value>,
+    pub frames: Vec<CallFrame>,
+    /// The main script's globals. Modules get their own.
+    pub globals: Globals,
+    pub open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    /// Installed exception handlers, innermost last.
+    pub handlers: Vec<Handler>,
+    /// Errors waiting for a `finally` block to finish before `RETHROW`
+    /// resumes them, with the thrown value if there was one.
+    pub pending: Vec<(LoxError, Option<Value>)>,
+    /// The value of the `throw` currently unwinding the stack. The error it
+    /// travels with only carries a message, so a handler picks the value up
+    /// from here.
+    pub thrown: Option<Value>,
+    /// The built-in `Error` class of the objects runtime errors become,
+    /// created by the first one caught.
+    error_class: Option<Rc<RefCell<Class>>>,
+    pub modules: ModuleRegistry<Module>,
+}
+
+fn clock_native(_vm: &mut Vm, _arguments: Vec<Value>) -> Result<Value, String> {
+    let since_the_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards");
+    Ok(Value::Number(since_the_epoch.as_secs_f64()))
+}
+
+fn str_native(vm: &mut Vm, arguments: Vec<Value>) -> Result<Value, String> {
+    let value = arguments
+        .into_iter()
+        .next()
+        .expect("str takes one argument");
+    vm.stringify(value)
+        .map(|text| Value::String(Rc::from(text)))
+        .map_err(|error| error.message().to_string())
+}
+
+impl Vm {
+    pub fn define_globals(&mut self) {
+        let mut globals = self.globals.borrow_mut();
+        globals.insert(
+            Rc::from("clock"),
+            Value::Native(NativeFunction {
+                arity: 0,
+                function: clock_native,
+            }),
+        );
+        globals.insert(
+            Rc::from("str"),
+            Value::Native(NativeFunction {
+                arity: 1,
+                function: str_native,
+            }),
+        );
+    }
+
+    pub fn interpret(&mut self, function: Rc<Function>) -> Result<(), LoxError> {
+        let closure = Rc::new(Closure {
+            function,
+            upvalues: Vec::new(),
+            globals: Rc::clone(&self.globals),
+        });
+        self.stack.push(Value::Closure(Rc::clone(&closure)));
+        self.call(closure, 0)?;
+        self.run(0)
+    }
+
+    /// The path and text of the imported module `error` was raised in, or
+    /// `None` for errors in the main script.
+    pub fn source_of(&self, error: &LoxError) -> Option<(&Path, &str)> {
+        self.modules.source_of(error)
+    }
+
+    /// Loads, runs and caches the module at `path`, which is relative to the
+    /// importing file, and binds it as `name`. Mirrors the interpreter's
+    /// `load_module`: the module runs with its own globals, which start out
+    /// holding only the natives.
+    fn import(&mut self, path: &str, name: &str) -> Result<Rc<Module>, LoxError> {
+        let mut modules = std::mem::take(&mut self.modules);
+        let found = modules.import(path, |message| self.error(message));
+        self.modules = modules;
+        let mut source = match found? {
+            Import::Loaded(module) => {
+                return Ok(Rc::new(Module {
+                    name: name.to_string(),
+                    path: module.path.clone(),
+                    globals: Rc::clone(&module.globals),
+                }))
+            }
+            Import::Load(source) => source,
+        };
+        let function = Compiler::new()
+            .in_file(source.file)
+            .compile(&mut source.statements)
+            .map_err(|errors| self.modules.set_aside(errors))?;
+
+        let natives = self
+            .globals
+            .borrow()
+            .iter()
+            .filter(|(_, value)| matches!(value, Value::Native(_)))
+            .map(|(name, value)| (Rc::clone(name), value.clone()))
+            .collect();
+        let globals = Rc::new(RefCell::new(natives));
+        let closure = Rc::new(Closure {
+            function,
+            upvalues: Vec::new(),
+            globals: Rc::clone(&globals),
+        });
+        let depth = self.frames.len();
+        self.stack.push(Value::Closure(Rc::clone(&closure)));
+        self.modules.enter(source.path.clone());
+        let result = self.call(closure, 0).and_then(|_| self.run(depth));
+        let module = result.map(|_| {
+            Rc::new(Module {
+                name: name.to_string(),
+                path: source.path,
+                globals,
+            })
+        });
+        self.modules.leave(module.as_ref().ok().cloned());
+        let module = module?;
+        // The module's top level returned nil.
+        self.pop();
+        Ok(module)
+    }
+
+    /// The text `print` shows for `value`. Instances whose class defines
+    /// `toString()` are converted by running it to completion, including
+    /// ones inside lists and maps.
+    pub fn stringify(&mut self, value: Value) -> Result<String, LoxError> {
+        let value = match Vm::to_string_method(&value) {
+            Some(method) => {
+                let (depth, height) = (self.frames.len(), self.stack.len());
+                self.stack.push(value);
+                let result = self.call(method, 0).and_then(|_| self.run(depth));
+                if let Err(err) = result {
+                    // Natives such as `str` recover from the error, so the
+                    // frames `toString()` left behind mustn't outlive it.
+                    self.frames.truncate(depth);
+                    self.stack.truncate(height);
+                    return Err(err);
+                }
+                self.pop()
+            }
+            None => value,
+        };
+        match value {
+            Value::Number(n) => Ok(n.to_string()),
+            value => value.fmt_collection(&mut |instance| self.stringify(instance.clone())),
+        }
+    }
+
+    fn to_string_method(value: &Value) -> Option<Rc<Closure>> {
+        match value {
+            Value::Instance(instance) => instance
+                .borrow()
+                .klass
+                .borrow()
+                .methods
+                .get("toString")
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    fn error(&self, message: String) -> LoxError {
+        let line_of = |frame: &CallFrame| frame.closure.function.chunk.line_at(frame.ip - 1);
+        let line = self.frames.last().map(line_of).unwrap_or(0);
+        let file = self
+            .frames
+            .last()
+            .map_or(0, |frame| frame.closure.function.file);
+        // Frame 0 is the script; every other frame was called from the one
+        // below it. Like the interpreter, only function calls are listed,
+        // not the top level of imported modules.
+        let trace = (1..self.frames.len())
+            .rev()
+            .filter(|&i| self.frames[i].closure.function.name != SCRIPT)
+            .map(|i| {
+                let function = self.frames[i].closure.function.name.clone();
+                (function, line_of(&self.frames[i - 1]))
+            })
+            .collect();
+        LoxError::Runtime {
+            token: None,
+            line,
+            file,
+            message,
+            note: None,
+            trace,
+        }
+    }
+
+    fn frame(&mut self) -> &mut CallFrame {
+        self.frames.last_mut().expect("no active call frame")
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let frame = self.frame();
+        let byte = frame.closure.function.chunk.code[frame.ip];
+        frame.ip += 1;
+        byte
+    }
+
+    fn read_short(&mut self) -> u16 {
+        let high = self.read_byte() as u16;
+        let low = self.read_byte() as u16;
+        (high << 8) | low
+    }
+
+    fn read_constant(&mut self) -> Constant {
+        let index = self.read_short() as usize;
+        self.frame().closure.function.chunk.constants[index].clone()
+    }
+
+    fn read_string(&mut self) -> Rc<str> {
+        match self.read_constant() {
+            Constant::String(s) => s,
+            _ => unreachable!("operand must be a string constant"),
+        }
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("value stack underflow")
+    }
+
+    fn peek(&self, distance: usize) -> &Value {
+        &self.stack[self.stack.len() - 1 - distance]
+    }
+
+    fn is_truthy(value: &Value) -> bool {
+        match value {
+            Value::Nil => false,
+            Value::Boolean(val) => *val,
+            _ => true,
+        }
+    }
+
+    /// Checks `index` against a list of `len` elements; `insert` may also
+    /// use `len` itself to append.
+    fn list_index(&self, index: &Value, len: usize, allow_end: bool) -> Result<usize, LoxError> {
+        let position = match index {
+            Value::Number(n) if n.fract() == 0.0 => *n,
+            _ => return Err(self.error(String::from("List index must be an integer."))),
+        };
+        let limit = if allow_end { len + 1 } else { len };
+        if position < 0.0 || position >= limit as f64 {
+            return Err(self.error(String::from("List index out of range.")));
+        }
+        Ok(position as usize)
+    }
+
+    fn call_list_method(&mut self, method: &ListMethod, arg_count: usize) -> Result<(), LoxError> {
+        if arg_count != method.arity() {
+            return Err(self.error(format!(
+                "Expected {} arguments but got {}.",
+                method.arity(),
+                arg_count
+            )));
+        }
+        let first_argument = self.stack.len() - arg_count;
+        let arguments = self.stack.split_off(first_argument);
+        let mut list = method.list.borrow_mut();
+        let result = match &*method.name {
+            "push" => {
+                list.push(arguments[0].clone());
+                Value::Nil
+            }
+            "pop" => match list.pop() {
+                Some(value) => value,
+                None => return Err(self.error(String::from("Can't pop from an empty list."))),
+            },
+            "len" => Value::Number(list.len() as f64),
+            "insert" => {
+                let index = self.list_index(&arguments[0], list.len(), true)?;
+                list.insert(index, arguments[1].clone());
+                Value::Nil
+            }
+            _ => {
+                let index = self.list_index(&arguments[0], list.len(), false)?;
+                list.remove(index)
+            }
+        };
+        self.pop();
+        self.stack.push(result);
+        Ok(())
+    }
+
+    fn map_key(&self, key: &Value) -> Result<MapKey, LoxError> {
+        key.to_map_key().ok_or_else(|| {
+            self.error(String::from(
+                "Map keys must be strings, numbers, booleans, nil, instances, classes or functions.",
+            ))
+        })
+    }
+
+    fn call_map_method(&mut self, method: &MapMethod, arg_count: usize) -> Result<(), LoxError> {
+        if arg_count != method.arity() {
+            return Err(self.error(format!(
+                "Expected {} arguments but got {}.",
+                method.arity(),
+                arg_count
+            )));
+        }
+        let first_argument = self.stack.len() - arg_count;
+        let arguments = self.stack.split_off(first_argument);
+        let mut map = method.map.borrow_mut();
+        let list = |values: Vec<Value>| Value::List(Rc::new(RefCell::new(values)));
+        let result = match &*method.name {
+            "keys" => list(map.keys().map(Value::from_map_key).collect()),
+            "values" => list(map.values().cloned().collect()),
+            "len" => Value::Number(map.len() as f64),
+            "has" => Value::Boolean(map.contains_key(&self.map_key(&arguments[0])?)),
+            _ => map
+                .shift_remove(&self.map_key(&arguments[0])?)
+                .unwrap_or_default(),
+        };
+        self.pop();
+        self.stack.push(result);
+        Ok(())
+    }
+
+    fn binary_numbers(&mut self) -> Result<(f64, f64), LoxError> {
+        match (self.peek(1), self.peek(0)) {
+            (Value::Number(left), Value::Number(right)) => {
+                let operands = (*left, *right);
+                self.stack.truncate(self.stack.len() - 2);
+                Ok(operands)
+            }
+            _ => Err(self.error(String::from("Operands must be numbers."))),
+        }
+    }
+
+    fn call(&mut self, closure: Rc<Closure>, arg_count: usize) -> Result<(), LoxError> {
+        if arg_count != closure.function.arity {
+            return Err(self.error(format!(
+                "Expected {} arguments but got {}.",
+                closure.function.arity, arg_count
+            )));
+        }
+        if self.frames.len() == FRAMES_MAX {
+            return Err(self.error(String::from("Stack overflow.")));
+        }
+        let slot_base = self.stack.len() - arg_count - 1;
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            slot_base,
+            discard_result: false,
+        });
+        Ok(())
+    }
+
+    fn call_value(&mut self, callee: Value, arg_count: usize) -> Result<(), LoxError> {
+        match callee {
+            Value::Closure(closure) => self.call(closure, arg_count),
+            Value::Native(native) => {
+                if arg_count != native.arity {
+                    return Err(self.error(format!(
+                        "Expected {} arguments but got {}.",
+                        native.arity, arg_count
+                    )));
+                }
+                let first_argument = self.stack.len() - arg_count;
+                let arguments = self.stack.split_off(first_argument);
+                let result =
+                    (native.function)(self, arguments).map_err(|message| self.error(message))?;
+                self.pop();
+                self.stack.push(result);
+                Ok(())
+            }
+            Value::Class(klass) => {
+                let instance = Value::Instance(Rc::new(RefCell::new(Instance {
+                    klass: Rc::clone(&klass),
+                    fields: HashMap::new(),
+                })));
+                let slot = self.stack.len() - arg_count - 1;
+                self.stack[slot] = instance;
+                let initializer = klass.borrow().methods.get("init").cloned();
+                match initializer {
+                    Some(initializer) => self.call(initializer, arg_count),
+                    None if arg_count != 0 => {
+                        Err(self.error(format!("Expected 0 arguments but got {}.", arg_count)))
+                    }
+                    None => Ok(()),
+                }
+            }
+            Value::BoundMethod(bound) => {
+                let slot = self.stack.len() - arg_count - 1;
+                self.stack[slot] = bound.receiver.clone();
+                self.call(Rc::clone(&bound.method), arg_count)
+            }
+            Value::ListMethod(method) => self.call_list_method(&method, arg_count),
+            Value::MapMethod(method) => self.call_map_method(&method, arg_count),
+            _ => Err(self.error(String::from("Can only call functions and classes."))),
+        }
+    }
+
+    fn capture_upvalue(&mut self, slot: usize) -> Rc<RefCell<Upvalue>> {
+        for upvalue in &self.open_upvalues {
+            if let Upvalue::Open(open_slot) = *upvalue.borrow() {
+                if open_slot == slot {
+                    return Rc::clone(upvalue);
+                }
+            }
+        }
+        let upvalue = Rc::new(RefCell::new(Upvalue::Open(slot)));
+        self.open_upvalues.push(Rc::clone(&upvalue));
+        upvalue
+    }
+
+    /// Moves every open upvalue at or above `last` off the stack.
+    fn close_upvalues(&mut self, last: usize) {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|upvalue| {
+            let slot = match *upvalue.borrow() {
+                Upvalue::Open(slot) => slot,
+                Upvalue::Closed(_) => return false,
+            };
+            if slot >= last {
+                *upvalue.borrow_mut() = Upvalue::Closed(stack[slot].clone());
+                return false;
+            }
+            true
+        });
+    }
+
+    fn bind_method(&mut self, klass: &Rc<RefCell<Class>>, name: &str, receiver: Value) -> bool {
+        let method = klass.borrow().methods.get(name).cloned();
+        match method {
+            Some(method) => {
+                self.stack.push(Value::BoundMethod(Rc::new(BoundMethod {
+                    receiver,
+                    method,
+                })));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Calls the bound method on top of the stack if it is a getter, so its
+    /// return value takes the method's place once the call finishes.
+    fn run_getter(&mut self) -> Result<(), LoxError> {
+        match self.peek(0) {
+            Value::BoundMethod(bound) if bound.method.function.is_getter => {
+                let callee = self.peek(0).clone();
+                self.call_value(callee, 0)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Pushes `klass.name`: a static field, or else a static method bound to
+    /// `receiver`. Returns false if the class has neither.
+    fn get_static(&mut self, klass: &Rc<RefCell<Class>>, name: &str, receiver: Value) -> bool {
+        let field = klass.borrow().find_field(name);
+        if let Some(value) = field {
+            self.stack.push(value);
+            return true;
+        }
+        self.bind_static_method(klass, name, receiver)
+    }
+
+    fn bind_static_method(
+        &mut self,
+        klass: &Rc<RefCell<Class>>,
+        name: &str,
+        receiver: Value,
+    ) -> bool {
+        let method = klass.borrow().find_static_method(name);
+        match method {
+            Some(method) => {
+                self.stack.push(Value::BoundMethod(Rc::new(BoundMethod {
+                    receiver,
+                    method,
+                })));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Calls the operator method for `symbol`, such as `__add__` for `+`,
+    /// when the left operand is an instance. The instance is already in the
+    /// receiver slot below the right operand. Returns false when the
+    /// built-in operator should run instead, which for `==` includes
+    /// classes without `__eq__`.
+    fn call_operator(&mut self, symbol: &str) -> Result<bool, LoxError> {
+        let instance = match self.peek(1) {
+            Value::Instance(instance) => Rc::clone(instance),
+            _ => return Ok(false),
+        };
+        let method_name = operator_method(symbol).expect("every binary operator has a method");
+        let klass = Rc::clone(&instance.borrow().klass);
+        let method = klass.borrow().methods.get(method_name).cloned();
+        match method {
+            Some(method) => {
+                self.call(method, 1)?;
+                Ok(true)
+            }
+            // Without `__add__`, an instance with `toString()` still
+            // concatenates with a string.
+            None if method_name == "__add__"
+                && matches!(self.peek(0), Value::String(_))
+                && Vm::to_string_method(self.peek(1)).is_some() =>
+            {
+                Ok(false)
+            }
+            None if method_name == "__eq__" => Ok(false),
+            None => Err(self.error(format!(
+                "Operator '{}' is not defined for {}; add an '{}' method.",
+                symbol,
+                klass.borrow().name,
+                method_name
+            ))),
+        }
+    }
+
+    /// The class of every error object, shared so that catching two errors
+    /// gives instances of the same class.
+    fn error_class(&mut self) -> Rc<RefCell<Class>> {
+        let klass = self.error_class.get_or_insert_with(|| {
+            Rc::new(RefCell::new(Class {
+                name: String::from("Error"),
+                methods: HashMap::new(),
+                static_methods: HashMap::new(),
+                setters: HashMap::new(),
+                fields: HashMap::new(),
+                superclass: None,
+            }))
+        });
+        Rc::clone(klass)
+    }
+
+    /// The value a `catch` clause receives for a runtime error raised by the
+    /// VM itself: an `Error` instance with `message` and `line` fields.
+    fn error_object(&mut self, error: &LoxError) -> Value {
+        let fields = HashMap::from([
+            (
+                String::from("message"),
+                Value::String(Rc::from(error.message())),
+            ),
+            (String::from("line"), Value::Number(error.line() as f64)),
+        ]);
+        Value::Instance(Rc::new(RefCell::new(Instance {
+            klass: self.error_class(),
+            fields,
+        })))
+    }
+
+    /// The message an uncaught `throw` reports. Rethrowing a caught error
+    /// object keeps its original message.
+    fn uncaught_message(&self, value: &Value) -> String {
+        if let (Value::Instance(instance), Some(error_class)) = (value, &self.error_class) {
+            let instance = instance.borrow();
+            if let Some(Value::String(message)) = instance.fields.get("message") {
+                if Rc::ptr_eq(&instance.klass, error_class) {
+                    return message.to_string();
+                }
+            }
+        }
+        match value {
+            Value::Number(n) => format!("Uncaught exception: {}", n),
+            _ => format!("Uncaught exception: {}", value),
+        }
+    }
+
+    /// Executes until the frame count drops back to `stop_depth`. Zero runs
+    /// the whole script; a nested run finishes one call and leaves its result
+    /// on the stack. Runtime errors unwind to the innermost handler installed
+    /// by this run, if any.
+    fn run(&mut self, stop_depth: usize) -> Result<(), LoxError> {
+        loop {
+            match self.execute(stop_depth) {
+                Ok(()) => return Ok(()),
+                Err(error) => self.unwind(error, stop_depth)?,
+            }
+        }
+    }
+
+    /// Resumes at the innermost handler above `stop_depth` after `error`,
+    /// or hands the error back when there is none.
+    fn unwind(&mut self, error: LoxError, stop_depth: usize) -> Result<(), LoxError> {
+        match self.handlers.last() {
+            Some(handler) if handler.frames > stop_depth => {}
+            _ => return Err(error),
+        }
+        let handler = self.handlers.pop().expect("a handler was found above");
+        let thrown = self.thrown.take();
+        self.frames.truncate(handler.frames);
+        self.close_upvalues(handler.stack_height);
+        self.stack.truncate(handler.stack_height);
+        self.pending.truncate(handler.pending);
+        if handler.finally {
+            self.pending.push((error, thrown));
+        } else {
+            let value = match thrown {
+                Some(value) => value,
+                None => self.error_object(&error),
+            };
+            self.stack.push(value);
+        }
+        self.frame().ip = handler.ip;
+        Ok(())
+    }
+
+    fn execute(&mut self, stop_depth: usize) -> Result<(), LoxError> {
+        loop {
+            let op = OpCode::from_byte(self.read_byte());
+            match op {
+                OpCode::CONSTANT => {
+                    let value = match self.read_constant() {
+                        Constant::Number(n) => Value::Number(n),
+                        Constant::String(s) => Value::String(s),
+                        Constant::Function(_) => unreachable!("functions are loaded by CLOSURE"),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::NIL => self.stack.push(Value::Nil),
+                OpCode::TRUE => self.stack.push(Value::Boolean(true)),
+                OpCode::FALSE => self.stack.push(Value::Boolean(false)),
+                OpCode::POP => {
+                    self.pop();
+                }
+                OpCode::GET_LOCAL => {
+                    let slot = self.read_byte() as usize;
+                    let base = self.frame().slot_base;
+                    self.stack.push(self.stack[base + slot].clone());
+                }
+                OpCode::SET_LOCAL => {
+                    let slot = self.read_byte() as usize;
+                    let base = self.frame().slot_base;
+                    self.stack[base + slot] = self.peek(0).clone();
+                }
+                OpCode::GET_GLOBAL => {
+                    let name = self.read_string();
+                    let value = self.frame().closure.globals.borrow().get(&name).cloned();
+                    match value {
+                        Some(value) => self.stack.push(value),
+                        None => return Err(self.error(format!("Undefined variable {}.", name))),
+                    }
+                }
+                OpCode::DEFINE_GLOBAL => {
+                    let name = self.read_string();
+                    let value = self.pop();
+                    self.frame()
+                        .closure
+                        .globals
+                        .borrow_mut()
+                        .insert(name, value);
+                }
+                OpCode::SET_GLOBAL => {
+                    let name = self.read_string();
+                    let value = self.peek(0).clone();
+                    let globals = Rc::clone(&self.frame().closure.globals);
+                    let assigned = match globals.borrow_mut().get_mut(&name) {
+                        Some(slot) => {
+                            *slot = value;
+                            true
+                        }
+                        None => false,
+                    };
+                    if !assigned {
+                        return Err(self.error(format!("Undefined variable {}.", name)));
+                    }
+                }
+                OpCode::GET_UPVALUE => {
+                    let index = self.read_byte() as usize;
+                    let upvalue = Rc::clone(&self.frame().closure.upvalues[index]);
+                    let value = match &*upvalue.borrow() {
+                        Upvalue::Open(slot) => self.stack[*slot].clone(),
+                        Upvalue::Closed(value) => value.clone(),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::SET_UPVALUE => {
+                    let index = self.read_byte() as usize;
+                    let upvalue = Rc::clone(&self.frame().closure.upvalues[index]);
+                    let value = self.peek(0).clone();
+                    let mut upvalue = upvalue.borrow_mut();
+                    match &mut *upvalue {
+                        Upvalue::Open(slot) => self.stack[*slot] = value,
+                        Upvalue::Closed(closed) => *closed = value,
+                    }
+                }
+                OpCode::GET_PROPERTY => {
+                    let name = self.read_string();
+                    let instance = match self.peek(0) {
+                        Value::Instance(instance) => Rc::clone(instance),
+                        Value::List(list) => {
+                            if !matches!(&*name, "push" | "pop" | "len" | "insert" | "remove") {
+                                return Err(self.error(format!("Undefined property '{}'.", name)));
+                            }
+                            let method = ListMethod {
+                                list: Rc::clone(list),
+                                name,
+                            };
+                            self.pop();
+                            self.stack.push(Value::ListMethod(Rc::new(method)));
+                            continue;
+                        }
+                        Value::Class(klass) => {
+                            let klass = Rc::clone(klass);
+                            let receiver = self.pop();
+                            if !self.get_static(&klass, &name, receiver) {
+                                return Err(self.error(format!("Undefined property '{}'.", name)));
+                            }
+                            self.run_getter()?;
+                            continue;
+                        }
+                        Value::Map(map) => {
+                            if !matches!(&*name, "keys" | "values" | "has" | "remove" | "len") {
+                                return Err(self.error(format!("Undefined property '{}'.", name)));
+                            }
+                            let method = MapMethod {
+                                map: Rc::clone(map),
+                                name,
+                            };
+                            self.pop();
+                            self.stack.push(Value::MapMethod(Rc::new(method)));
+                            continue;
+                        }
+                        Value::Module(module) => {
+                            let member = module.globals.borrow().get(&name).cloned();
+                            let Some(member) = member else {
+                                return Err(self.error(format!(
+                                    "Module '{}' has no member '{}'.",
+                                    module.name, name
+                                )));
+                            };
+                            self.pop();
+                            self.stack.push(member);
+                            continue;
+                        }
+                        _ => {
+                            return Err(self.error(String::from("Only instances have properties.")))
+                        }
+                    };
+                    let field = instance.borrow().fields.get(&*name).cloned();
+                    if let Some(value) = field {
+                        self.pop();
+                        self.stack.push(value);
+                    } else {
+                        let klass = Rc::clone(&instance.borrow().klass);
+                        let receiver = self.pop();
+                        if !self.bind_method(&klass, &name, receiver) {
+                            return Err(self.error(format!("Undefined property '{}'.", name)));
+                        }
+                        self.run_getter()?;
+                    }
+                }
+                OpCode::SET_PROPERTY => {
+                    let name = self.read_string();
+                    let instance = match self.peek(1) {
+                        Value::Instance(instance) => Rc::clone(instance),
+                        Value::Class(klass) => {
+                            let klass = Rc::clone(klass);
+                            let value = self.pop();
+                            klass
+                                .borrow_mut()
+                                .fields
+                                .insert(name.to_string(), value.clone());
+                            self.pop();
+                            self.stack.push(value);
+                            continue;
+                        }
+                        _ => {
+                            return Err(self.error(String::from("Only instances have properties.")))
+                        }
+                    };
+                    let setter = instance
+                        .borrow()
+                        .klass
+                        .borrow()
+                        .setters
+                        .get(&*name)
+                        .cloned();
+                    if let Some(setter) = setter {
+                        // Leave the value as the expression's result, then call
+                        // the setter with the instance in slot zero.
+                        let value = self.pop();
+                        let receiver = self.pop();
+                        self.stack.push(value.clone());
+                        self.stack.push(receiver);
+                        self.stack.push(value);
+                        self.call(setter, 1)?;
+                        self.frame().discard_result = true;
+                        continue;
+                    }
+                    let value = self.pop();
+                    instance
+                        .borrow_mut()
+                        .fields
+                        .insert(name.to_string(), value.clone());
+                    self.pop();
+                    self.stack.push(value);
+                }
+                OpCode::BUILD_LIST => {
+                    let count = self.read_short() as usize;
+                    let elements = self.stack.split_off(self.stack.len() - count);
+                    self.stack
+                        .push(Value::List(Rc::new(RefCell::new(elements))));
+                }
+                OpCode::BUILD_MAP => {
+                    let count = self.read_short() as usize;
+                    let entries = self.stack.split_off(self.stack.len() - 2 * count);
+                    let mut map = IndexMap::new();
+                    for pair in entries.chunks(2) {
+                        map.insert(self.map_key(&pair[0])?, pair[1].clone());
+                    }
+                    self.stack.push(Value::Map(Rc::new(RefCell::new(map))));
+                }
+                OpCode::GET_INDEX => {
+                    let index = self.pop();
+                    let value = match self.pop() {
+                        Value::List(list) => {
+                            let list = list.borrow();
+                            let position = self.list_index(&index, list.len(), false)?;
+                            list[position].clone()
+                        }
+                        Value::Map(map) => {
+                            let key = self.map_key(&index)?;
+                            let value = map.borrow().get(&key).cloned();
+                            match value {
+                                Some(value) => value,
+                                None => return Err(self.error(format!("Undefined key {}.", key))),
+                            }
+                        }
+                        _ => {
+                            return Err(
+                                self.error(String::from("Only lists and maps can be indexed."))
+                            )
+                        }
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::SET_INDEX => {
+                    let value = self.pop();
+                    let index = self.pop();
+                    match self.pop() {
+                        Value::List(list) => {
+                            let mut list = list.borrow_mut();
+                            let position = self.list_index(&index, list.len(), false)?;
+                            list[position] = value.clone();
+                        }
+                        Value::Map(map) => {
+                            let key = self.map_key(&index)?;
+                            map.borrow_mut().insert(key, value.clone());
+                        }
+                        _ => {
+                            return Err(
+                                self.error(String::from("Only lists and maps can be indexed."))
+                            )
+                        }
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::GET_SUPER => {
+                    let name = self.read_string();
+                    let superclass = match self.pop() {
+                        Value::Class(klass) => klass,
+                        _ => return Err(self.error(String::from("Superclass must be a class."))),
+                    };
+                    let receiver = self.pop();
+                    // In a static method the receiver is the class itself.
+                    let found = match receiver {
+                        Value::Class(_) => self.bind_static_method(&superclass, &name, receiver),
+                        _ => self.bind_method(&superclass, &name, receiver),
+                    };
+                    if !found {
+                        return Err(self.error(format!("Undefined property {}.", name)));
+                    }
+                    self.run_getter()?;
+                }
+                OpCode::EQUAL => {
+                    if self.call_operator("==")? {
+                        continue;
+                    }
+                    let right = self.pop();
+                    let left = self.pop();
+                    self.stack.push(Value::Boolean(left == right));
+                }
+                OpCode::GREATER => {
+                    if self.call_operator(">")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Boolean(left > right));
+                }
+                OpCode::GREATER_EQUAL => {
+                    if self.call_operator(">=")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Boolean(left >= right));
+                }
+                OpCode::LESS => {
+                    if self.call_operator("<")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Boolean(left < right));
+                }
+                OpCode::LESS_EQUAL => {
+                    if self.call_operator("<=")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Boolean(left <= right));
+                }
+                OpCode::ADD => {
+                    if self.call_operator("+")? {
+                        continue;
+                    }
+                    let converts = match (self.peek(1), self.peek(0)) {
+                        (Value::String(_), other) | (other, Value::String(_)) => {
+                            Vm::to_string_method(other).is_some()
+                        }
+                        _ => false,
+                    };
+                    if converts {
+                        let right = self.pop();
+                        let left = self.pop();
+                        let text = self.stringify(left)? + &self.stringify(right)?;
+                        self.stack.push(Value::String(Rc::from(text)));
+                        continue;
+                    }
+                    let result = match (self.peek(1), self.peek(0)) {
+                        (Value::Number(left), Value::Number(right)) => Value::Number(left + right),
+                        (Value::String(left), Value::String(right)) => {
+                            Value::String(Rc::from(format!("{}{}", left, right)))
+                        }
+                        _ => {
+                            return Err(self.error(String::from(
+                                "Operands must be two numbers or two strings",
+                            )))
+                        }
+                    };
+                    self.stack.truncate(self.stack.len() - 2);
+                    self.stack.push(result);
+                }
+                OpCode::SUBTRACT => {
+                    if self.call_operator("-")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Number(left - right));
+                }
+                OpCode::MULTIPLY => {
+                    if self.call_operator("*")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Number(left * right));
+                }
+                OpCode::DIVIDE => {
+                    if self.call_operator("/")? {
+                        continue;
+                    }
+                    let (left, right) = self.binary_numbers()?;
+                    self.stack.push(Value::Number(left / right));
+                }
+                OpCode::NOT => {
+                    let value = self.pop();
+                    self.stack.push(Value::Boolean(!Vm::is_truthy(&value)));
+                }
+                OpCode::NEGATE => match self.pop() {
+                    Value::Number(n) => self.stack.push(Value::Number(-n)),
+                    _ => return Err(self.error(String::from("Operand must be a number."))),
+                },
+                OpCode::PRINT => {
+                    let value = self.pop();
+                    println!("{}", self.stringify(value)?);
+                }
+                OpCode::JUMP => {
+                    let offset = self.read_short() as usize;
+                    self.frame().ip += offset;
+                }
+                OpCode::JUMP_IF_FALSE => {
+                    let offset = self.read_short() as usize;
+                    if !Vm::is_truthy(self.peek(0)) {
+                        self.frame().ip += offset;
+                    }
+                }
+                OpCode::LOOP => {
+                    let offset = self.read_short() as usize;
+                    self.frame().ip -= offset;
+                }
+                OpCode::CALL => {
+                    let arg_count = self.read_byte() as usize;
+                    let callee = self.peek(arg_count).clone();
+                    self.call_value(callee, arg_count)?;
+                }
+                OpCode::CLOSURE => {
+                    let function = match self.read_constant() {
+                        Constant::Function(function) => function,
+                        _ => unreachable!("CLOSURE operand must be a function"),
+                    };
+                    let mut upvalues = Vec::with_capacity(function.upvalue_count);
+                    for _ in 0..function.upvalue_count {
+                        let is_local = self.read_byte() == 1;
+                        let index = self.read_byte() as usize;
+                        if is_local {
+                            let slot = self.frame().slot_base + index;
+                            upvalues.push(self.capture_upvalue(slot));
+                        } else {
+                            upvalues.push(Rc::clone(&self.frame().closure.upvalues[index]));
+                        }
+                    }
+                    let globals = Rc::clone(&self.frame().closure.globals);
+                    self.stack.push(Value::Closure(Rc::new(Closure {
+                        function,
+                        upvalues,
+                        globals,
+                    })));
+                }
+                OpCode::CLOSE_UPVALUE => {
+                    self.close_upvalues(self.stack.len() - 1);
+                    self.pop();
+                }
+                OpCode::RETURN => {
+                    let result = self.pop();
+                    let frame = self.frames.pop().expect("no active call frame");
+                    self.close_upvalues(frame.slot_base);
+                    self.stack.truncate(frame.slot_base);
+                    if self.frames.is_empty() {
+                        return Ok(());
+                    }
+                    if !frame.discard_result {
+                        self.stack.push(result);
+                    }
+                    if self.frames.len() == stop_depth {
+                        return Ok(());
+                    }
+                }
+                OpCode::CLASS => {
+                    let name = self.read_string();
+                    self.stack.push(Value::Class(Rc::new(RefCell::new(Class {
+                        name: name.to_string(),
+                        methods: HashMap::new(),
+                        static_methods: HashMap::new(),
+                        setters: HashMap::new(),
+                        fields: HashMap::new(),
+                        superclass: None,
+                    }))));
+                }
+                OpCode::INHERIT => {
+                    let superclass = match self.peek(1) {
+                        Value::Class(klass) => Rc::clone(klass),
+                        _ => return Err(self.error(String::from("Superclass must be a class."))),
+                    };
+                    if let Value::Class(subclass) = self.peek(0) {
+                        let methods = superclass.borrow().methods.clone();
+                        let setters = superclass.borrow().setters.clone();
+                        let mut subclass = subclass.borrow_mut();
+                        subclass.methods.extend(methods);
+                        subclass.setters.extend(setters);
+                        subclass.superclass = Some(superclass);
+                    }
+                    self.pop();
+                }
+                OpCode::METHOD => {
+                    let name = self.read_string();
+                    let method = match self.pop() {
+                        Value::Closure(closure) => closure,
+                        _ => unreachable!("METHOD operand must be a closure"),
+                    };
+                    if let Value::Class(klass) = self.peek(0) {
+                        klass.borrow_mut().methods.insert(name.to_string(), method);
+                    }
+                }
+                OpCode::SETTER => {
+                    let name = self.read_string();
+                    let setter = match self.pop() {
+                        Value::Closure(closure) => closure,
+                        _ => unreachable!("SETTER operand must be a closure"),
+                    };
+                    if let Value::Class(klass) = self.peek(0) {
+                        klass.borrow_mut().setters.insert(name.to_string(), setter);
+                    }
+                }
+                OpCode::TRY | OpCode::TRY_FINALLY => {
+                    let offset = self.read_short() as usize;
+                    let handler = Handler {
+                        frames: self.frames.len(),
+                        stack_height: self.stack.len(),
+                        pending: self.pending.len(),
+                        ip: self.frame().ip + offset,
+                        finally: op == OpCode::TRY_FINALLY,
+                    };
+                    self.handlers.push(handler);
+                }
+                OpCode::END_TRY => {
+                    self.handlers.pop();
+                }
+                OpCode::THROW => {
+                    let value = self.pop();
+                    let error = self.error(self.uncaught_message(&value));
+                    self.thrown = Some(value);
+                    return Err(error);
+                }
+                OpCode::RETHROW => {
+                    let (error, thrown) = self.pending.pop().expect("no error was set aside");
+                    self.thrown = thrown;
+                    return Err(error);
+                }
+                OpCode::DROP_PENDING => {
+                    self.pending.pop();
+                }
+                OpCode::IMPORT => {
+                    let path = self.read_string();
+                    let name = self.read_string();
+                    let module = self.import(&path, &name)?;
+                    self.stack.push(Value::Module(module));
+                }
+                OpCode::STATIC_METHOD => {
+                    let name = self.read_string();
+                    let method = match self.pop() {
+                        Value::Closure(closure) => closure,
+                        _ => unreachable!("STATIC_METHOD operand must be a closure"),
+                    };
+                    if let Value::Class(klass) = self.peek(0) {
+                        klass
+                            .borrow_mut()
+                            .static_methods
+                            .insert(name.to_string(), method);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod Object;
+pub mod Value;
+pub mod Vm;
@@ -0,0 +1,123 @@
+//! The `Lox` session API as a host program uses it.
+
+use codecrafters_interpreter::{enums::LoxError::LoxError, LiteralValue, Lox};
+
+fn session() -> Lox {
+    let mut lox = Lox::new();
+    lox.capture_output();
+    lox
+}
+
+fn error_message(result: Result<(), Vec<LoxError>>) -> String {
+    let errors = result.expect_err("the program should fail");
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    errors[0].message().to_string()
+}
+
+#[test]
+fn captured_output_is_taken_once() {
+    let mut lox = session();
+    lox.run_source("print 1; print \"two\"; print [3, nil];")
+        .unwrap();
+    assert_eq!(lox.take_output(), "1\ntwo\n[3, nil]\n");
+    assert_eq!(lox.take_output(), "");
+    lox.run_source("print 4;").unwrap();
+    assert_eq!(lox.take_output(), "4\n");
+}
+
+#[test]
+fn globals_cross_between_host_and_script() {
+    let mut lox = session();
+    lox.set_global("limit", LiteralValue::Number(3.0));
+    lox.run_source("var total = 0; for (var i = 0; i < limit; i = i + 1) total = total + i;")
+        .unwrap();
+    assert!(lox.get_global("total") == Some(LiteralValue::Number(3.0)));
+    assert!(lox.get_global("i").is_none());
+}
+
+#[test]
+fn typed_natives_check_arity_and_argument_types() {
+    let mut lox = session();
+    lox.register_typed("add", |a: f64, b: f64| a + b);
+    lox.register_typed("repeat", |s: String, n: i64| -> Result<String, String> {
+        if n < 0 {
+            return Err(String::from("count must not be negative"));
+        }
+        Ok(s.repeat(n as usize))
+    });
+
+    lox.run_source("print add(1, 2); print repeat(\"ab\", 2);")
+        .unwrap();
+    assert_eq!(lox.take_output(), "3\nabab\n");
+
+    assert_eq!(
+        error_message(lox.run_source("add(1);")),
+        "Expected 2 arguments but got 1."
+    );
+    assert_eq!(
+        error_message(lox.run_source("add(1, \"x\");")),
+        "Expected number for argument 2 of 'add' but got string."
+    );
+    assert_eq!(
+        error_message(lox.run_source("repeat(\"a\", 1.5);")),
+        "Expected integer for argument 2 of 'repeat' but got number."
+    );
+    assert_eq!(
+        error_message(lox.run_source("repeat(\"a\", -1);")),
+        "count must not be negative"
+    );
+}
+
+#[test]
+fn the_session_survives_errors() {
+    let mut lox = session();
+    lox.run_source("var kept = \"yes\"; fun fail() { { var local = 1; return local + nil; } }")
+        .unwrap();
+    assert!(lox.run_source("fail();").is_err());
+    assert!(lox.run_source("throw \"uncaught\";").is_err());
+
+    // Back at global scope: new globals land in the globals, and the
+    // uncaught value isn't what the next `catch` sees.
+    lox.run_source("var after = kept; try { fail(); } catch (e) { print e.message; }")
+        .unwrap();
+    assert_eq!(
+        lox.take_output(),
+        "Operands must be two numbers or two strings\n"
+    );
+    assert!(lox.get_global("after") == Some(LiteralValue::String(String::from("yes"))));
+}
+
+#[test]
+fn eval_expression_reports_errors() {
+    let mut lox = session();
+    let Err(errors) = lox.eval_expression("fun () { print ; }") else {
+        panic!("a bad statement in a lambda body should fail");
+    };
+    assert_eq!(errors[0].message(), "Expect expression.");
+
+    let Err(errors) = lox.eval_expression("1 + nil") else {
+        panic!("adding nil should fail");
+    };
+    assert_eq!(errors[0].exit_code(), 70);
+
+    assert!(
+        lox.eval_expression("(fun (x) { return x * 2; })(21)")
+            .unwrap()
+            == LiteralValue::Number(42.0)
+    );
+}
+
+#[test]
+fn only_the_builtin_error_class_rethrows_its_message() {
+    let mut lox = session();
+    lox.run_source("class Error { init(m) { this.message = m; } }")
+        .unwrap();
+    assert_eq!(
+        error_message(lox.run_source("throw Error(\"mine\");")),
+        "Uncaught exception: Error instance"
+    );
+    assert_eq!(
+        error_message(lox.run_source("try { nil + 1; } catch (e) { throw e; }")),
+        "Operands must be two numbers or two strings"
+    );
+}
@@ -0,0 +1,46 @@
+//! How the binary reports errors with and without `--error-format=`.
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Runs `source` and returns stderr. Stderr is a pipe here, never a
+/// terminal, so leaving out the flag picks the plain format.
+fn stderr_of(name: &str, source: &str, flag: Option<&str>) -> String {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, source).expect("failed to write the test program");
+    let mut command = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"));
+    command.arg("run").arg(&path).stderr(Stdio::piped());
+    if let Some(flag) = flag {
+        command.arg(flag);
+    }
+    let output = command.output().expect("failed to run the interpreter");
+    assert_eq!(output.status.code(), Some(65));
+    String::from_utf8_lossy(&output.stderr)
+        .replace(path.to_str().expect("test paths are UTF-8"), "main.lox")
+}
+
+const MISSING_EXPRESSION: &str = "print 1;\nvar a = ;\n";
+
+#[test]
+fn plain_errors_name_the_line() {
+    for flag in [None, Some("--error-format=plain")] {
+        assert!(
+            stderr_of("plain.lox", MISSING_EXPRESSION, flag)
+                .ends_with("[line 2] Error at ';': Expect expression.\n"),
+            "{:?}",
+            flag
+        );
+    }
+}
+
+#[test]
+fn rich_errors_point_at_the_source() {
+    assert!(
+        stderr_of("rich.lox", MISSING_EXPRESSION, Some("--error-format=rich")).ends_with(
+            "error: Expect expression.\n --> main.lox:2:9\n  |\n2 | var a = ;\n  |         ^\n"
+        )
+    );
+}
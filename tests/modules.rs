@@ -0,0 +1,182 @@
+//! `import` as the `run` command sees it, in both the tree-walker and the
+//! bytecode VM, with the modules written to a directory of their own for
+//! each test.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Writes `files` under a fresh directory named `name` and returns the path
+/// of the first one, the script to run.
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&root);
+    for (path, source) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().expect("module paths have a parent"))
+            .expect("failed to create the module directory");
+        fs::write(&path, source).expect("failed to write the module");
+    }
+    root.join(files[0].0)
+}
+
+fn run(script: &Path, vm: bool) -> (Option<i32>, String, String) {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"));
+    // Run from elsewhere so paths can only resolve against the importing file.
+    command
+        .arg("run")
+        .arg(script)
+        .current_dir(env!("CARGO_MANIFEST_DIR"));
+    if vm {
+        command.arg("--vm");
+    }
+    let output = command.output().expect("failed to run the interpreter");
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn each_import_keeps_its_own_name() {
+    let script = project(
+        "aliases",
+        &[
+            (
+                "main.lox",
+                "import \"m.lox\";\nimport \"m.lox\" as mm;\nprint m;\nprint mm;\nprint m == mm;\n",
+            ),
+            ("m.lox", "var x = 1;\n"),
+        ],
+    );
+    for vm in [false, true] {
+        assert_eq!(
+            run(&script, vm),
+            (
+                Some(0),
+                String::from("<module m>\n<module mm>\ntrue\n"),
+                String::from("Logs from your program will appear here!\n")
+            )
+        );
+    }
+}
+
+#[test]
+fn errors_in_modules_name_the_module_and_keep_their_traceback() {
+    let script = project(
+        "module_traceback",
+        &[
+            ("main.lox", "import \"lib.lox\";\nlib.fail();\n"),
+            ("lib.lox", "fun fail() {\n  return 1 + nil;\n}\n"),
+        ],
+    );
+    let module = script.with_file_name("lib.lox");
+    let expected = format!(
+        "Logs from your program will appear here!\n\
+         {}: Operands must be two numbers or two strings\n[line 2]\n\
+         at fail (line 2)\nat <script> (line 2)\n",
+        module.canonicalize().unwrap().display()
+    );
+    for vm in [false, true] {
+        assert_eq!(
+            run(&script, vm),
+            (Some(70), String::new(), expected.clone())
+        );
+    }
+}
+
+#[test]
+fn paths_are_relative_to_the_importing_file() {
+    let script = project(
+        "relative",
+        &[
+            ("app/main.lox", "import \"lib/a.lox\";\nprint a.greeting;\n"),
+            (
+                "app/lib/a.lox",
+                "import \"b.lox\";\nimport \"../../shared/c.lox\";\nvar greeting = b.word + \" \" + c.word;\n",
+            ),
+            ("app/lib/b.lox", "var word = \"hello\";\n"),
+            ("shared/c.lox", "var word = \"world\";\n"),
+        ],
+    );
+    for vm in [false, true] {
+        let (code, stdout, _) = run(&script, vm);
+        assert_eq!(
+            (code, stdout.as_str()),
+            (Some(0), "hello world\n"),
+            "vm: {}",
+            vm
+        );
+    }
+}
+
+#[test]
+fn a_module_runs_once_however_often_it_is_imported() {
+    let script = project(
+        "cached",
+        &[
+            (
+                "main.lox",
+                "import \"counter.lox\";\nimport \"user.lox\";\nimport \"./counter.lox\" as again;\n\
+                 counter.bump();\nuser.bump_twice();\nprint again.count;\nprint counter.count;\n",
+            ),
+            (
+                "counter.lox",
+                "print \"loading counter\";\nvar count = 0;\nfun bump() { count = count + 1; }\n",
+            ),
+            (
+                "user.lox",
+                "import \"counter.lox\";\nfun bump_twice() { counter.bump(); counter.bump(); }\n",
+            ),
+        ],
+    );
+    for vm in [false, true] {
+        let (code, stdout, _) = run(&script, vm);
+        assert_eq!(
+            (code, stdout.as_str()),
+            (Some(0), "loading counter\n3\n3\n"),
+            "vm: {}",
+            vm
+        );
+    }
+}
+
+#[test]
+fn import_cycles_are_runtime_errors() {
+    let script = project(
+        "cycle",
+        &[
+            (
+                "main.lox",
+                "print \"start\";\nimport \"a.lox\";\nprint \"unreached\";\n",
+            ),
+            ("a.lox", "import \"b.lox\";\n"),
+            ("b.lox", "var x = 1;\nimport \"a.lox\";\n"),
+            ("self.lox", "import \"self.lox\";\n"),
+        ],
+    );
+    let directory = script.parent().unwrap().canonicalize().unwrap();
+    for vm in [false, true] {
+        let (code, stdout, stderr) = run(&script, vm);
+        assert_eq!((code, stdout.as_str()), (Some(70), "start\n"), "vm: {}", vm);
+        assert!(
+            stderr.ends_with(&format!(
+                "{}: Import cycle detected: a.lox -> b.lox -> a.lox.\n[line 2]\n",
+                directory.join("b.lox").display()
+            )),
+            "{}",
+            stderr
+        );
+
+        let (code, _, stderr) = run(&script.with_file_name("self.lox"), vm);
+        assert_eq!(code, Some(70));
+        assert!(
+            stderr.contains("Import cycle detected: self.lox -> self.lox."),
+            "{}",
+            stderr
+        );
+    }
+}
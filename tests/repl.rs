@@ -0,0 +1,80 @@
+//! The `repl` command fed from a pipe: the editor reads stdin line by line
+//! without printing prompts, so a session's output is just its echoes and
+//! errors.
+
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Runs `input` through one REPL session and returns stdout and stderr.
+fn session(input: &str) -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .arg("repl")
+        // Keep the history file out of the real home directory.
+        .env("HOME", Path::new(env!("CARGO_TARGET_TMPDIR")))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start the repl");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("failed to write to the repl");
+    let output = child.wait_with_output().expect("the repl exits at EOF");
+    assert_eq!(output.status.code(), Some(0));
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn open_braces_continue_the_input_on_the_next_line() {
+    let (stdout, stderr) = session(
+        "fun add(a, b) {\n  if (a > 0) {\n    return a + b;\n  }\n  return b;\n}\n\
+         add(1, 2)\n\
+         var brace = \"{\"; // {\n\
+         brace\n\
+         /* { */ class A {\n  m() { return \"m\"; }\n}\n\
+         A().m()\n",
+    );
+    assert_eq!(stdout, "3\n{\nm\n");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn the_session_carries_on_after_errors() {
+    let (stdout, stderr) = session(
+        "var a = 1;\n\
+         print missing;\n\
+         var = 2;\n\
+         fun broken() {\n  return nil + 1;\n}\n\
+         broken()\n\
+         a = a + 1;\n\
+         a\n",
+    );
+    assert_eq!(stdout, "2\n");
+    for message in [
+        "error: Undefined variable missing.",
+        "error: Expect variable name.",
+        "error: Operands must be two numbers or two strings",
+    ] {
+        assert!(stderr.contains(message), "{:?} not in\n{}", message, stderr);
+    }
+}
+
+#[test]
+fn a_runaway_call_does_not_end_the_session() {
+    let (stdout, stderr) = session(
+        "fun runaway(n) { return runaway(n + 1); }\n\
+         runaway(0);\n\
+         \"still here\"\n",
+    );
+    assert_eq!(stdout, "still here\n");
+    assert!(stderr.contains("Stack overflow."), "{}", stderr);
+}
@@ -0,0 +1,226 @@
+//! Runs Lox programs through the built binary and checks that the
+//! tree-walker (`run`) and the bytecode VM (`run --vm`) behave the same,
+//! and as the corpus in `tests/lox` expects.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// The line the binary writes to stderr before anything else.
+const BANNER: &str = "Logs from your program will appear here!\n";
+
+fn lox(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(args)
+        .output()
+        .expect("failed to run the interpreter")
+}
+
+/// Writes `source` to a file of its own for the tests that need a program
+/// the corpus can't hold.
+fn script(name: &str, source: &str) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, source).expect("failed to write the test program");
+    path
+}
+
+fn run(path: &Path, vm: bool) -> (Option<i32>, String, String) {
+    let path = path.to_str().expect("test paths are UTF-8");
+    let output = match vm {
+        true => lox(&["run", path, "--vm"]),
+        false => lox(&["run", path]),
+    };
+    (
+        output.status.code(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+/// What a corpus program should do, read from the files next to it:
+/// `name.out` holds its stdout, and a program that fails also has
+/// `name.err` with its stderr and `name.code` with its exit code. Stderr is
+/// compared without the banner the binary prints first, and with the
+/// corpus directory cut from the paths of imported modules.
+///
+/// Run with `LOX_BLESS=1` to rewrite the files from the tree-walker's
+/// output, then review the diff.
+fn expected(program: &Path) -> (Option<i32>, String, String) {
+    let read = |extension: &str| fs::read_to_string(program.with_extension(extension)).ok();
+    let stdout = read("out").unwrap_or_else(|| panic!("{} has no .out file", program.display()));
+    let code = read("code").map_or(0, |code| {
+        code.trim().parse().expect("exit codes are numbers")
+    });
+    (Some(code), stdout, read("err").unwrap_or_default())
+}
+
+fn bless(program: &Path, (code, stdout, stderr): &(Option<i32>, String, String)) {
+    let write = |extension: &str, contents: Option<String>| {
+        let path = program.with_extension(extension);
+        match contents {
+            Some(contents) => fs::write(&path, contents).expect("failed to bless"),
+            None => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    };
+    let code = code.expect("the interpreter exited normally");
+    let failed = code != 0;
+    write("out", Some(stdout.clone()));
+    write("err", failed.then(|| stderr.clone()));
+    write("code", failed.then(|| format!("{}\n", code)));
+}
+
+#[test]
+fn both_runtimes_match_the_expected_output_of_the_corpus() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lox");
+    let mut programs: Vec<PathBuf> = fs::read_dir(&corpus)
+        .expect("the corpus directory exists")
+        .map(|entry| entry.expect("readable corpus entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lox"))
+        .collect();
+    programs.sort();
+    assert!(!programs.is_empty(), "no programs in {}", corpus.display());
+
+    let directory = format!(
+        "{}/",
+        corpus
+            .canonicalize()
+            .expect("the corpus has a path")
+            .display()
+    );
+    let normalize = |(code, stdout, stderr): (Option<i32>, String, String)| {
+        let stderr = stderr
+            .strip_prefix(BANNER)
+            .unwrap_or(&stderr)
+            .replace(&directory, "");
+        (code, stdout, stderr)
+    };
+    let blessing = std::env::var_os("LOX_BLESS").is_some();
+
+    let mut mismatches = Vec::new();
+    for program in &programs {
+        let tree_walker = normalize(run(program, false));
+        if blessing {
+            bless(program, &tree_walker);
+        }
+        let expected = expected(program);
+        for (runtime, actual) in [
+            ("run", tree_walker),
+            ("run --vm", normalize(run(program, true))),
+        ] {
+            if actual != expected {
+                mismatches.push(format!(
+                    "{} ({})\n  expected: {:?}\n  actual:   {:?}",
+                    program.display(),
+                    runtime,
+                    expected,
+                    actual
+                ));
+            }
+        }
+    }
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn runtime_errors_exit_with_70() {
+    let path = script(
+        "runtime_error.lox",
+        "print \"before\";\nprint 1 + nil;\nprint \"after\";\n",
+    );
+    for vm in [false, true] {
+        let (code, stdout, stderr) = run(&path, vm);
+        assert_eq!(code, Some(70));
+        assert_eq!(stdout, "before\n");
+        assert!(stderr.ends_with("Operands must be two numbers or two strings\n[line 2]\n"));
+    }
+}
+
+#[test]
+fn runtime_errors_in_functions_print_a_traceback() {
+    let path = script(
+        "traceback.lox",
+        "fun f() { return nil + 1; }\nfun g() { f(); }\ng();\n",
+    );
+    for vm in [false, true] {
+        let (code, _, stderr) = run(&path, vm);
+        assert_eq!(code, Some(70));
+        assert!(
+            stderr.ends_with(
+                "Operands must be two numbers or two strings\n[line 1]\n\
+                 at f (line 1)\nat g (line 2)\nat <script> (line 3)\n"
+            ),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn recursion_goes_as_deep_as_the_call_limit() {
+    let path = script(
+        "deep_recursion.lox",
+        "fun depth(n) {\n  if (n == 0) return 0;\n  return 1 + depth(n - 1);\n}\nprint depth(4094);\n",
+    );
+    for vm in [false, true] {
+        let (code, stdout, _) = run(&path, vm);
+        assert_eq!((code, stdout.as_str()), (Some(0), "4094\n"));
+    }
+}
+
+#[test]
+fn recursion_from_nested_statements_goes_as_deep_as_the_call_limit() {
+    let path = script(
+        "nested_recursion.lox",
+        "class A {
+  depth(n) {
+    if (n == 0) return 0;
+    {
+      var x = 1;
+      while (true) {
+        for (var i = 0; i < 1; i = i + 1) {
+          if (n > 0) return 1 + x * this.depth(n - 1);
+        }
+      }
+    }
+  }
+}
+print A().depth(4094);
+",
+    );
+    for vm in [false, true] {
+        let (code, stdout, stderr) = run(&path, vm);
+        assert_eq!((code, stdout.as_str()), (Some(0), "4094\n"), "{}", stderr);
+    }
+}
+
+#[test]
+fn runaway_recursion_is_a_stack_overflow_error() {
+    let path = script(
+        "runaway_recursion.lox",
+        "fun runaway(n) {\n  return runaway(n + 1);\n}\nrunaway(0);\n",
+    );
+    for vm in [false, true] {
+        let (code, stdout, stderr) = run(&path, vm);
+        assert_eq!(code, Some(70));
+        assert_eq!(stdout, "");
+        assert!(stderr.contains("Stack overflow.\n[line 2]\n"), "{}", stderr);
+        assert!(
+            stderr.contains("at runaway (line 2)\n... 4076 more\nat runaway (line 2)\n"),
+            "{}",
+            stderr
+        );
+        assert_eq!(
+            stderr
+                .lines()
+                .skip_while(|line| !line.starts_with("Stack"))
+                .count(),
+            23,
+            "{}",
+            stderr
+        );
+    }
+}
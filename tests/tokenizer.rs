@@ -0,0 +1,196 @@
+//! What the tokenizer makes of keywords, identifiers, string literals and
+//! comments.
+
+use codecrafters_interpreter::{enums::TokenType::TokenType, utils::tokenizer::tokenize};
+
+fn scan(source: &str) -> Vec<(TokenType, String)> {
+    let result = tokenize(source.to_string());
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    result
+        .tokens
+        .into_iter()
+        .map(|token| (token.token_type, token.token_value))
+        .filter(|(token_type, _)| *token_type != TokenType::EOF)
+        .collect()
+}
+
+/// Scans `source`, which must have lex errors, and returns them as the
+/// plain format prints them, along with the line of every token.
+fn scan_errors(source: &str) -> (Vec<String>, Vec<(TokenType, u32)>) {
+    let result = tokenize(source.to_string());
+    assert!(!result.errors.is_empty(), "{:?}", result.tokens);
+    (
+        result.errors.iter().map(ToString::to_string).collect(),
+        result
+            .tokens
+            .into_iter()
+            .map(|token| (token.token_type, token.line))
+            .collect(),
+    )
+}
+
+fn lines(source: &str) -> Vec<(TokenType, u32)> {
+    let result = tokenize(source.to_string());
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    result
+        .tokens
+        .into_iter()
+        .map(|token| (token.token_type, token.line))
+        .collect()
+}
+
+#[test]
+fn keywords_are_whole_words() {
+    for word in [
+        "try2",
+        "import_x",
+        "catcher",
+        "finally_",
+        "throws",
+        "breaker",
+        "continue1",
+        "and_or",
+        "class9",
+        "fun_",
+        "forx",
+        "nil0",
+        "this_",
+        "var_",
+    ] {
+        assert_eq!(
+            scan(word),
+            [(TokenType::IDENTIFIER, word.to_string())],
+            "{}",
+            word
+        );
+    }
+}
+
+#[test]
+fn keywords_still_end_at_punctuation() {
+    assert_eq!(
+        scan("try{import\"m\";"),
+        [
+            (TokenType::TRY, String::from("try")),
+            (TokenType::LEFT_BRACE, String::from("{")),
+            (TokenType::IMPORT, String::from("import")),
+            (TokenType::STRING, String::from("m")),
+            (TokenType::SEMICOLON, String::from(";")),
+        ]
+    );
+}
+
+#[test]
+fn escapes_are_decoded() {
+    assert_eq!(
+        scan(r#""tab\tquote\"slash\\cr\rnl\n" "\u{48}\u{e9}\u{1F600}""#),
+        [
+            (
+                TokenType::STRING,
+                String::from("tab\tquote\"slash\\cr\rnl\n")
+            ),
+            (TokenType::STRING, String::from("Hé😀")),
+        ]
+    );
+}
+
+#[test]
+fn bad_escapes_are_reported_and_scanning_resumes_after_the_string() {
+    let (errors, tokens) = scan_errors(
+        "\"\\q\" a\n\"\\u{110000}\" b\n\"\\u12\" \"\\u{}\" \"\\u{1234567}\" c\n\"\\\n\" d",
+    );
+    assert_eq!(
+        errors,
+        [
+            "[line 1] Error: Unknown escape sequence '\\q'.",
+            "[line 2] Error: '110000' is not a valid unicode code point.",
+            "[line 3] Error: Invalid unicode escape; expected \\u{...} with 1 to 6 hex digits.",
+            "[line 3] Error: Invalid unicode escape; expected \\u{...} with 1 to 6 hex digits.",
+            "[line 3] Error: Invalid unicode escape; expected \\u{...} with 1 to 6 hex digits.",
+            "[line 4] Error: Unfinished escape sequence.",
+        ]
+    );
+    assert_eq!(
+        tokens,
+        [
+            (TokenType::IDENTIFIER, 1),
+            (TokenType::IDENTIFIER, 2),
+            (TokenType::IDENTIFIER, 3),
+            (TokenType::IDENTIFIER, 5),
+            (TokenType::EOF, 5),
+        ]
+    );
+}
+
+#[test]
+fn multi_line_strings_keep_their_newlines_and_count_them() {
+    assert_eq!(
+        scan("\"one\ntwo\n\nthree\""),
+        [(TokenType::STRING, String::from("one\ntwo\n\nthree"))]
+    );
+    assert_eq!(
+        lines("var s = \"one\ntwo\n\nthree\";\nprint s;"),
+        [
+            (TokenType::VAR, 1),
+            (TokenType::IDENTIFIER, 1),
+            (TokenType::EQUAL, 1),
+            (TokenType::STRING, 1),
+            (TokenType::SEMICOLON, 4),
+            (TokenType::PRINT, 5),
+            (TokenType::IDENTIFIER, 5),
+            (TokenType::SEMICOLON, 5),
+            (TokenType::EOF, 5),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_strings_are_reported_at_their_first_line() {
+    let (errors, tokens) = scan_errors("print 1;\nvar s = \"one\ntwo\n");
+    assert_eq!(errors, ["[line 2] Error: Unterminated string."]);
+    assert_eq!(tokens.last(), Some(&(TokenType::EOF, 4)));
+}
+
+#[test]
+fn block_comments_nest_and_count_their_lines() {
+    assert_eq!(
+        lines("a /* one /* two\n */ still\n comment */ b /**/ c\n/* /* /* */ */ */ d"),
+        [
+            (TokenType::IDENTIFIER, 1),
+            (TokenType::IDENTIFIER, 3),
+            (TokenType::IDENTIFIER, 3),
+            (TokenType::IDENTIFIER, 4),
+            (TokenType::EOF, 4),
+        ]
+    );
+}
+
+#[test]
+fn comment_markers_in_strings_and_line_comments_are_text() {
+    assert_eq!(
+        scan("\"/* not a comment\" // /* nor this\n*/"),
+        [
+            (TokenType::STRING, String::from("/* not a comment")),
+            (TokenType::STAR, String::from("*")),
+            (TokenType::SLASH, String::from("/")),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_block_comments_are_reported_at_their_first_line() {
+    for source in [
+        "a\n/* open",
+        "a\n/* open /* closed */\n",
+        "a\n/* /* */ */ /* /*",
+    ] {
+        let (errors, tokens) = scan_errors(source);
+        assert_eq!(
+            errors,
+            ["[line 2] Error: Unterminated comment."],
+            "{:?}",
+            source
+        );
+        assert_eq!(tokens[0], (TokenType::IDENTIFIER, 1), "{:?}", source);
+    }
+}